@@ -0,0 +1,310 @@
+//! Conway polyhedron operators on polyhedron faces for Rust
+//!
+//! notation (applied from right to left): "tI" "dk C" "ggD"
+//! - seeds: T (tetra) C (cube) O (octa) I (icosa) D (dodeca)
+//! - operators: d (dual) k (kis) a (ambo) t (truncate) j (join)
+//!   e (expand) g (gyro) s (snub) c (chamfer)
+//!
+
+use std::collections::{HashMap, HashSet};
+
+use num::Float;
+
+use crate::polyhedron::{TUV, Polyhedron};
+use crate::polyhedron::{tetra::Tetra, cube::Cube, octa::Octa};
+use crate::polyhedron::{icosa::Icosa, dodeca::Dodeca};
+use crate::{merge_vertices, calc_cg_with_volume, fan_indexed, dot_f3};
+use crate::error::PHError;
+
+/// Conway polygon faces (vertices shared by faces)
+#[derive(Debug, Clone)]
+pub struct Conway<F: Float> {
+  /// vtx
+  pub vtx: Vec<[F; 3]>,
+  /// faces: Vec n of counter clockwise polygon (from outside)
+  pub faces: Vec<Vec<u32>>
+}
+
+/// Conway
+impl<F: Float + std::fmt::Debug> Conway<F> where F: std::iter::Sum {
+  /// construct from the polyhedron (each face to the one polygon)
+  /// - p: precision to weld duplicate vertices
+  pub fn from_polyhedron<T: TUV<F> + ?Sized>(ph: &T, p: F) ->
+    Result<Self, PHError> {
+    let vs = ph.ref_vtx();
+    let m = merge_vertices(vs, p);
+    let mut remap = HashMap::<u32, u32>::new();
+    let mut vtx = vec![];
+    let mut faces = vec![];
+    for f in ph.ref_tri().iter() {
+      // boundary = directed edges without the reversed edge in the face
+      let mut es = HashSet::<(u32, u32)>::new();
+      for t in f.iter() {
+        let t = t.map(|i| m[i as usize]);
+        if t[0] == t[1] || t[1] == t[2] || t[2] == t[0] { continue; }
+        for k in 0..3 {
+          let (a, b) = (t[k], t[(k + 1) % 3]);
+          if !es.remove(&(b, a)) { es.insert((a, b)); } // inside the face
+        }
+      }
+      let next = es.iter().copied().collect::<HashMap<_, _>>();
+      let Some(&s) = next.keys().min() else { continue; };
+      let mut poly = vec![s];
+      let mut a = next[&s];
+      while a != s && poly.len() < next.len() {
+        poly.push(a);
+        a = next[&a]; // every boundary vertex has the next
+      }
+      if a != s || poly.len() != es.len() {
+        return Err(PHError::Degenerate(
+          "face is not the one polygon".to_string()));
+      }
+      faces.push(poly.into_iter().map(|i| *remap.entry(i).or_insert_with(|| {
+        vtx.push(vs[i as usize]);
+        vtx.len() as u32 - 1
+      })).collect());
+    }
+    Ok(Conway{vtx, faces})
+  }
+
+  /// construct from the notation (seed is the last uppercase letter)
+  pub fn parse(s: &str) -> Result<Self, PHError> {
+    let s = s.trim();
+    let e = || PHError::InvalidParameter(format!("unknown seed in {:?}", s));
+    let l = <F>::from(1).unwrap();
+    let p = <F>::from(1e-6).unwrap();
+    let seed = match s.chars().last().ok_or_else(e)? {
+    'T' => Self::from_polyhedron(&Tetra::new(l).ph, p)?,
+    'C' => Self::from_polyhedron(&Cube::new(l).ph, p)?,
+    'O' => Self::from_polyhedron(&Octa::new(l).ph, p)?,
+    'I' => Self::from_polyhedron(&Icosa::new(l).ph, p)?,
+    'D' => Self::from_polyhedron(&Dodeca::new(l).ph, p)?,
+    _ => return Err(e())
+    };
+    seed.apply(&s[..s.len() - 1])
+  }
+
+  /// apply operators (from right to left, whitespace is ignored)
+  pub fn apply(self, ops: &str) -> Result<Self, PHError> {
+    ops.chars().rev().try_fold(self, |c, o| Ok(match o {
+    'd' => c.dual(),
+    'k' => c.kis(),
+    'a' => c.ambo(),
+    't' => c.truncate(),
+    'j' => c.join(),
+    'e' => c.expand(),
+    'g' => c.gyro(),
+    's' => c.snub(),
+    'c' => c.chamfer(),
+    _ if o.is_whitespace() => c,
+    _ => return Err(PHError::InvalidParameter(
+      format!("unknown operator {:?}", o)))
+    }))
+  }
+
+  /// to polyhedron (each polygon to the one face of fan triangles)
+  pub fn to_polyhedron(&self) -> Polyhedron<F> {
+    let vtx = self.vtx.clone();
+    let tri = fan_indexed(&self.faces);
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    Polyhedron{vtx, tri, uv: vec![], vol, center: false}
+  }
+
+  /// dual (polar reciprocal about the midsphere)
+  pub fn dual(self) -> Self {
+    let ne = self.faces.iter().map(|f| f.len()).sum::<usize>();
+    let rr = self.faces.iter().flat_map(|f|
+      (0..f.len()).map(|i| {
+        let m = mid(&self.vtx[f[i] as usize],
+          &self.vtx[f[(i + 1) % f.len()] as usize], <F>::from(0.5).unwrap());
+        dot_f3(&m, &m).sqrt()
+      }).collect::<Vec<_>>()
+    ).sum::<F>() / <F>::from(ne).unwrap();
+    let vtx = self.faces.iter().map(|f| {
+      let c = self.centroid(f);
+      let n = self.normal(f);
+      let d = dot_f3(&n, &c);
+      if d <= F::epsilon() { c } else { n.map(|p| p * rr * rr / d) }
+    }).collect::<Vec<_>>();
+    let around = self.around();
+    let faces = around.into_iter().filter(|a| !a.is_empty()).map(|a|
+      a.into_iter().map(|(fi, _)| fi as u32).collect()).collect();
+    Conway{vtx, faces}
+  }
+
+  /// kis (pyramid on each face, apex at the centroid)
+  pub fn kis(self) -> Self {
+    let n = self.vtx.len() as u32;
+    let mut vtx = self.vtx.clone();
+    vtx.extend(self.faces.iter().map(|f| self.centroid(f)));
+    let faces = self.faces.iter().enumerate().flat_map(|(fi, f)|
+      (0..f.len()).map(move |i|
+        vec![f[i], f[(i + 1) % f.len()], n + fi as u32])
+    ).collect();
+    Conway{vtx, faces}
+  }
+
+  /// ambo (vertices on the middle of edges)
+  pub fn ambo(self) -> Self {
+    self.cut(<F>::from(0.5).unwrap(), true)
+  }
+
+  /// truncate (cut each vertex at 1/3 of edges)
+  pub fn truncate(self) -> Self {
+    self.cut(<F>::from(1.0 / 3.0).unwrap(), false)
+  }
+
+  /// join (dual of ambo)
+  pub fn join(self) -> Self {
+    self.ambo().dual()
+  }
+
+  /// expand (ambo of ambo)
+  pub fn expand(self) -> Self {
+    self.ambo().ambo()
+  }
+
+  /// gyro (each n-gon to n pentagons)
+  pub fn gyro(self) -> Self {
+    let t = <F>::from(1.0 / 3.0).unwrap();
+    let mut vtx = self.vtx.clone();
+    let cs = vtx.len() as u32;
+    vtx.extend(self.faces.iter().map(|f| self.centroid(f)));
+    let mut ids = HashMap::<(u32, u32), u32>::new(); // directed edge: 1/3
+    for f in self.faces.iter() {
+      for i in 0..f.len() {
+        let (a, b) = (f[i], f[(i + 1) % f.len()]);
+        for (a, b) in [(a, b), (b, a)] {
+          ids.entry((a, b)).or_insert_with(|| {
+            vtx.push(mid(&self.vtx[a as usize], &self.vtx[b as usize], t));
+            vtx.len() as u32 - 1
+          });
+        }
+      }
+    }
+    let faces = self.faces.iter().enumerate().flat_map(|(fi, f)| {
+      let n = f.len();
+      (0..n).map(|i| {
+        let (a, b, d) = (f[(i + n - 1) % n], f[i], f[(i + 1) % n]);
+        vec![cs + fi as u32, ids[&(a, b)], ids[&(b, a)], b, ids[&(b, d)]]
+      }).collect::<Vec<_>>()
+    }).collect();
+    Conway{vtx, faces}
+  }
+
+  /// snub (dual of gyro of dual)
+  pub fn snub(self) -> Self {
+    self.dual().gyro().dual()
+  }
+
+  /// chamfer (inset each face, edges to hexagons)
+  pub fn chamfer(self) -> Self {
+    let t = <F>::from(1.0 / 3.0).unwrap();
+    let mut vtx = self.vtx.clone();
+    let mut ids = HashMap::<(usize, u32), u32>::new(); // (face, vertex): inset
+    let faces = self.faces.iter().enumerate().map(|(fi, f)| {
+      let c = self.centroid(f);
+      f.iter().map(|&v| *ids.entry((fi, v)).or_insert_with(|| {
+        vtx.push(mid(&self.vtx[v as usize], &c, t));
+        vtx.len() as u32 - 1
+      })).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    let es = self.edge_faces();
+    let mut hex = es.iter().filter(|(&(a, b), _)| a < b).map(|(&(a, b), &fi)| {
+      let gi = es[&(b, a)];
+      vec![ids[&(fi, b)], ids[&(fi, a)], a, ids[&(gi, a)], ids[&(gi, b)], b]
+    }).collect::<Vec<_>>();
+    hex.sort();
+    Conway{vtx, faces: faces.into_iter().chain(hex).collect()}
+  }
+
+  /// cut each vertex at t of edges (merge points at the middle when m)
+  fn cut(self, t: F, m: bool) -> Self {
+    let mut vtx = vec![];
+    let mut ids = HashMap::<(u32, u32), u32>::new(); // directed edge: point
+    let mut id = |a: u32, b: u32| {
+      let k = if m { (a.min(b), a.max(b)) } else { (a, b) };
+      *ids.entry(k).or_insert_with(|| {
+        vtx.push(mid(&self.vtx[a as usize], &self.vtx[b as usize], t));
+        vtx.len() as u32 - 1
+      })
+    };
+    let mut faces = self.faces.iter().map(|f| {
+      let n = f.len();
+      (0..n).flat_map(|i| {
+        let (a, b) = (f[i], f[(i + 1) % n]);
+        if m { vec![id(a, b)] } else { vec![id(a, b), id(b, a)] }
+      }).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    for (v, a) in self.around().into_iter().enumerate() {
+      if a.is_empty() { continue; }
+      faces.push(a.into_iter().map(|(_, u)| id(v as u32, u)).collect());
+    }
+    Conway{vtx, faces}
+  }
+
+  /// directed edge: face index
+  fn edge_faces(&self) -> HashMap<(u32, u32), usize> {
+    self.faces.iter().enumerate().flat_map(|(fi, f)|
+      (0..f.len()).map(move |i|
+        ((f[i], f[(i + 1) % f.len()]), fi))
+    ).collect()
+  }
+
+  /// counter clockwise (face, previous vertex) around each vertex
+  fn around(&self) -> Vec<Vec<(usize, u32)>> {
+    let es = self.edge_faces();
+    let prev = |fi: usize, v: u32| {
+      let f = &self.faces[fi];
+      let i = f.iter().position(|&u| u == v).unwrap();
+      f[(i + f.len() - 1) % f.len()]
+    };
+    let mut a = vec![vec![]; self.vtx.len()];
+    for (fi, f) in self.faces.iter().enumerate() {
+      for &v in f.iter() {
+        if !a[v as usize].is_empty() { continue; }
+        let mut g = fi;
+        loop {
+          let u = prev(g, v);
+          a[v as usize].push((g, u));
+          match es.get(&(v, u)) {
+          Some(&h) if h != fi => g = h,
+          _ => break
+          }
+          if a[v as usize].len() > self.faces.len() { break; } // not manifold
+        }
+      }
+    }
+    a
+  }
+
+  /// centroid of the face
+  fn centroid(&self, f: &[u32]) -> [F; 3] {
+    let n = <F>::from(f.len()).unwrap();
+    let o = <F>::from(0).unwrap();
+    f.iter().fold([o, o, o], |s, &i| {
+      let v = self.vtx[i as usize];
+      [s[0] + v[0], s[1] + v[1], s[2] + v[2]]
+    }).map(|p| p / n)
+  }
+
+  /// normal of the face (Newell, normalized)
+  fn normal(&self, f: &[u32]) -> [F; 3] {
+    let o = <F>::from(0).unwrap();
+    let n = (0..f.len()).fold([o, o, o], |s, i| {
+      let a = self.vtx[f[i] as usize];
+      let b = self.vtx[f[(i + 1) % f.len()] as usize];
+      [
+        s[0] + (a[1] - b[1]) * (a[2] + b[2]),
+        s[1] + (a[2] - b[2]) * (a[0] + b[0]),
+        s[2] + (a[0] - b[0]) * (a[1] + b[1])]
+    });
+    let d = dot_f3(&n, &n).sqrt();
+    if d == o { n } else { n.map(|p| p / d) }
+  }
+}
+
+/// point at t from a to b
+fn mid<F: Float>(a: &[F; 3], b: &[F; 3], t: F) -> [F; 3] {
+  [0, 1, 2].map(|k| a[k] + (b[k] - a[k]) * t)
+}
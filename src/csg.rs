@@ -0,0 +1,644 @@
+//! CSG (union difference intersection) on closed polyhedron faces for Rust
+//!
+//! intersection curves are decided by the exact orientation predicate
+//! (floating point filter, exact expansion arithmetic and simulation of
+//! simplicity on the ties), triangles crossed by the curves are split along
+//! them and the fragments are classified by the winding number
+//!
+//! coplanar (within p) overlapped faces are split along the edges of the
+//! other and kept once by the direction of the normals
+//!
+//! faces of a keep fi, faces of b follow as fi + (number of faces of a)
+//!
+
+use std::collections::HashMap;
+
+use num::Float;
+
+use crate::{merge_vertices, calc_cg_with_volume};
+use crate::{sub_f3, cross_f3, dot_f3};
+use crate::polyhedron::{TUV, Polyhedron};
+use crate::query::{Bvh, winding_number, closest_point};
+use crate::triangulate::{polygon_area, group_contours, triangulate};
+use crate::validate::validate;
+use crate::error::PHError;
+
+/// CsgOp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOp {
+  /// a + b
+  Union,
+  /// a - b
+  Difference,
+  /// a * b
+  Intersection,
+  /// (a - b) + (b - a)
+  SymmetricDifference
+}
+
+/// two sum (sum and the round off error)
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+  let x = a + b;
+  let bv = x - a;
+  let av = x - bv;
+  (x, (a - av) + (b - bv))
+}
+
+/// two product (product and the round off error)
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+  let x = a * b;
+  (x, a.mul_add(b, -x))
+}
+
+/// grow the expansion (nonoverlapping, increasing magnitude) by the value
+fn grow(e: &[f64], b: f64) -> Vec<f64> {
+  let mut q = b;
+  let mut h = Vec::with_capacity(e.len() + 1);
+  for &c in e.iter() {
+    let (s, r) = two_sum(q, c);
+    if r != 0.0 { h.push(r); }
+    q = s;
+  }
+  if q != 0.0 { h.push(q); }
+  h
+}
+
+/// expansion times the value
+fn scale(e: &[f64], b: f64) -> Vec<f64> {
+  e.iter().fold(vec![], |s, &c| {
+    let (x, y) = two_prod(c, b);
+    grow(&grow(&s, y), x)
+  })
+}
+
+/// exact determinant as the expansion (Laplace on the first row)
+fn det_exact(m: &[Vec<f64>]) -> Vec<f64> {
+  if m.len() == 1 { return vec![m[0][0]]; }
+  let mut s = vec![];
+  for j in 0..m.len() {
+    if m[0][j] == 0.0 { continue; }
+    let minor = m[1..].iter().map(|r| r.iter().enumerate()
+      .filter(|&(k, _)| k != j).map(|(_, &v)| v).collect())
+      .collect::<Vec<Vec<f64>>>();
+    let a = if j % 2 == 0 { m[0][j] } else { -m[0][j] };
+    s = scale(&det_exact(&minor), a).iter().fold(s, |s, &c| grow(&s, c));
+  }
+  s
+}
+
+/// sign of the expansion
+fn sign(e: &[f64]) -> i8 {
+  e.last().map_or(0, |&x| if x > 0.0 { 1 } else { -1 })
+}
+
+/// leading term of the perturbation (global ranks in descending order and
+/// the coefficient) when the orient is exactly 0
+type Lead = Option<(Vec<usize>, f64)>;
+
+/// orient (positive when d is above the counter clockwise a b c)
+/// returns the sign (never 0) and the leading term of the perturbation
+/// - g: distinct global ids of a b c d (order of the perturbation)
+/// - p: points of the global ids
+fn orient(g: [usize; 4], p: &[[f64; 3]]) -> (i8, Lead) {
+  let mut o = [0, 1, 2, 3];
+  o.sort_by_key(|&k| g[k]);
+  let odd = (0..4).flat_map(|i| (i + 1..4).map(move |j| (i, j)))
+    .filter(|&(i, j)| o[i] > o[j]).count() % 2 == 1;
+  let v = o.map(|k| p[g[k]]);
+  let a = [0, 1, 2].map(|j| v[1][j] - v[0][j]);
+  let b = [0, 1, 2].map(|j| v[2][j] - v[0][j]);
+  let c = [0, 1, 2].map(|j| v[3][j] - v[0][j]);
+  let d = a[0] * (b[1] * c[2] - b[2] * c[1]) + a[1] * (b[2] * c[0]
+    - b[0] * c[2]) + a[2] * (b[0] * c[1] - b[1] * c[0]); // -det4 sorted
+  let perm = a[0].abs() * ((b[1] * c[2]).abs() + (b[2] * c[1]).abs())
+    + a[1].abs() * ((b[2] * c[0]).abs() + (b[0] * c[2]).abs())
+    + a[2].abs() * ((b[0] * c[1]).abs() + (b[1] * c[0]).abs());
+  let f = if odd { 1.0 } else { -1.0 }; // orient = -det4 unsorted
+  if d.abs() > 1e-14 * perm { return (if d * f < 0.0 { 1 } else { -1 }, None); }
+  let m = v.iter().map(|r| vec![r[0], r[1], r[2], 1.0]).collect::<Vec<_>>();
+  let d4 = sign(&det_exact(&m));
+  if d4 != 0 { return ((d4 as f64 * f) as i8, None); }
+  // simulation of simplicity: perturbation of rank 3 row + col
+  for bits in 1u32..4096 {
+    let rc = (0..12).filter(|b| bits >> b & 1 == 1).map(|b| (b / 3, b % 3))
+      .collect::<Vec<_>>();
+    if (1..rc.len()).any(|i| rc[i].0 == rc[i - 1].0) { continue; }
+    let mut mp = m.clone();
+    for &(r, c) in rc.iter() {
+      mp[r] = (0..4).map(|k| if k == c { 1.0 } else { 0.0 }).collect();
+    }
+    let e = det_exact(&mp);
+    if e.is_empty() { continue; }
+    let mut rk = rc.iter().map(|&(r, c)| 3 * g[o[r]] + c).collect::<Vec<_>>();
+    rk.reverse();
+    let x = e.iter().sum::<f64>() * f;
+    return (if x > 0.0 { 1 } else { -1 }, Some((rk, x)));
+  }
+  (1, Some((vec![], 1.0))) // never
+}
+
+/// crossing of the edges of a and b (sorted global ids of the both edges)
+type EdgePair = ((usize, usize), (usize, usize));
+
+/// edge (sorted global ids) crossing the triangle (of b, triangle)
+type EdgeTri = ((usize, usize), (bool, usize));
+
+/// clipped range of the segment (t0, edge at t0, t1, edge at t1)
+type Clip = (f64, Option<usize>, f64, Option<usize>);
+
+/// Pierce points (edge of the one crossing the triangle of the other)
+/// global ids of the pierce points follow the global points
+struct Pierce {
+  /// global points
+  pts: Vec<[f64; 3]>,
+  /// edge crossing the triangle to the global id
+  cache: HashMap<EdgeTri, Option<usize>>,
+  /// crossing of the edges of the coplanar triangles to the global id
+  xs: HashMap<EdgePair, usize>,
+  /// pierce points
+  pp: Vec<[f64; 3]>,
+  /// (global id, edge) the pierce point is on
+  on: Vec<(usize, (usize, usize))>,
+  /// (global id, corners) of the triangle the point is in
+  near: Vec<(usize, [usize; 3])>
+}
+
+/// Pierce
+impl Pierce {
+  /// construct
+  fn new(pts: Vec<[f64; 3]>) -> Self {
+    Pierce{pts, cache: HashMap::new(), xs: HashMap::new(), pp: vec![],
+      on: vec![], near: vec![]}
+  }
+  /// push the pierce point (returns the global id)
+  fn push(&mut self, q: [f64; 3], es: &[(usize, usize)], t: &[usize; 3]) ->
+    usize {
+    let k = self.pts.len() + self.pp.len();
+    self.pp.push(q);
+    self.on.extend(es.iter().map(|&e| (k, e)));
+    self.near.push((k, *t));
+    k
+  }
+  /// global id of the pierce point of the edge crossing the triangle
+  fn get(&mut self, e: (usize, usize), tk: (bool, usize), t: &[usize; 3]) ->
+    Option<usize> {
+    if let Some(&r) = self.cache.get(&(e, tk)) { return r; }
+    let r = self.calc(e, t).map(|q| self.push(q, &[e], t));
+    self.cache.insert((e, tk), r);
+    r
+  }
+  /// calc the pierce point
+  fn calc(&self, e: (usize, usize), t: &[usize; 3]) -> Option<[f64; 3]> {
+    let p = &self.pts;
+    let (sp, lp) = orient([t[0], t[1], t[2], e.0], p);
+    let (sq, lq) = orient([t[0], t[1], t[2], e.1], p);
+    if sp == sq { return None; }
+    let s = [(0, 1), (1, 2), (2, 0)].map(|(i, j)|
+      orient([e.0, e.1, t[i], t[j]], p).0);
+    if s[0] != s[1] || s[1] != s[2] { return None; }
+    let k = match (lp, lq) {
+    (Some((rp, cp)), Some((rq, cq))) => match rp.cmp(&rq) { // in the plane
+    std::cmp::Ordering::Less => 1.0,
+    std::cmp::Ordering::Greater => 0.0,
+    std::cmp::Ordering::Equal => (cp / (cp - cq)).clamp(0.0, 1.0)
+    },
+    _ => {
+      let (a, b, c) = (p[t[0]], p[t[1]], p[t[2]]);
+      let n = cross_f3(&sub_f3(&b, &a), &sub_f3(&c, &a));
+      let dp = dot_f3(&n, &sub_f3(&p[e.0], &a));
+      let dq = dot_f3(&n, &sub_f3(&p[e.1], &a));
+      if dp == dq { 0.5 } else { (dp / (dp - dq)).clamp(0.0, 1.0) }
+    }
+    };
+    let (u, v) = (p[e.0], p[e.1]);
+    Some([0, 1, 2].map(|j| u[j] + (v[j] - u[j]) * k))
+  }
+  /// true when the vertices of tb are on the plane of ta (within p)
+  fn is_coplanar(&self, ta: &[usize; 3], tb: &[usize; 3], p: f64) -> bool {
+    let c = ta.map(|i| self.pts[i]);
+    let n = cross_f3(&sub_f3(&c[1], &c[0]), &sub_f3(&c[2], &c[0]));
+    let l = dot_f3(&n, &n).sqrt();
+    tb.iter().all(|&v| dot_f3(&n, &sub_f3(&self.pts[v], &c[0])).abs() <= p * l)
+  }
+  /// segments on the coplanar triangles ta and tb
+  /// (edges of the one clipped by the other, crossings keyed by the edges)
+  /// returns (on tb, global ids of the ends)
+  fn coplanar(&mut self, ta: &[usize; 3], tb: &[usize; 3]) ->
+    Vec<(bool, usize, usize)> {
+    let mut r = vec![];
+    for (on_b, t, o) in [(false, ta, tb), (true, tb, ta)] {
+      let c = t.map(|i| self.pts[i]);
+      let (ax, ay) = axes(&c);
+      let t2 = c.map(|q| [q[ax], q[ay]]);
+      for j in 0..3 {
+        let (u, v) = (o[j].min(o[(j + 1) % 3]), o[j].max(o[(j + 1) % 3]));
+        let (pu, pv) = (self.pts[u], self.pts[v]);
+        let Some((t0, j0, t1, j1)) =
+          clip(&[pu[ax], pu[ay]], &[pv[ax], pv[ay]], &t2) else { continue; };
+        let ends = [(t0, j0, u), (t1, j1, v)].map(|(s, je, w)| {
+          let Some(i) = je else { self.near.push((w, *t)); return w; };
+          let (x, y) = (t[i], t[(i + 1) % 3]);
+          let f = (x.min(y), x.max(y));
+          let key = if on_b { ((u, v), f) } else { (f, (u, v)) };
+          if let Some(&k) = self.xs.get(&key) { return k; }
+          let q = [0, 1, 2].map(|k| pu[k] + (pv[k] - pu[k]) * s);
+          let k = self.push(q, &[f, (u, v)], t);
+          self.xs.insert(key, k);
+          k
+        });
+        r.push((on_b, ends[0], ends[1]));
+      }
+    }
+    r
+  }
+}
+
+/// clip the segment u v by the counter clockwise triangle t (2D)
+fn clip(u: &[f64; 2], v: &[f64; 2], t: &[[f64; 2]; 3]) -> Option<Clip> {
+  let (mut t0, mut j0, mut t1, mut j1) = (0.0, None, 1.0, None);
+  for j in 0..3 {
+    let (a, b) = (t[j], t[(j + 1) % 3]);
+    let d = [b[0] - a[0], b[1] - a[1]];
+    let eps = 1e-12 * (d[0] * d[0] + d[1] * d[1]);
+    let f = |q: &[f64; 2]| d[0] * (q[1] - a[1]) - d[1] * (q[0] - a[0]);
+    let (fu, fv) = (f(u), f(v));
+    if fu < -eps && fv < -eps { return None; }
+    if fu < -eps {
+      let s = fu / (fu - fv);
+      if s > t0 { (t0, j0) = (s, Some(j)); }
+    } else if fv < -eps {
+      let s = fu / (fu - fv);
+      if s < t1 { (t1, j1) = (s, Some(j)); }
+    }
+  }
+  (t1 - t0 > 1e-12).then_some((t0, j0, t1, j1))
+}
+
+/// Operand (welded global ids of the flat triangles)
+struct Operand {
+  /// (fi, ti) of the flat triangles
+  ft: Vec<(usize, usize)>,
+  /// global ids
+  g: Vec<[usize; 3]>,
+  /// flat index of (fi, ti)
+  flat: Vec<Vec<usize>>
+}
+
+/// Operand
+impl Operand {
+  /// construct (appends the welded points to the global points)
+  /// - off: offset of the output vertex id
+  /// - src: output vertex id of the global points
+  fn new<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], p: F,
+    off: u32, pts: &mut Vec<[f64; 3]>, src: &mut Vec<u32>) -> Self {
+    let m = merge_vertices(vtx, p);
+    let mut ids = vec![0; vtx.len()];
+    for i in 0..vtx.len() {
+      if m[i] as usize == i {
+        ids[i] = pts.len();
+        pts.push(vtx[i].map(|x| x.to_f64().unwrap()));
+        src.push(off + i as u32);
+      } else {
+        ids[i] = ids[m[i] as usize];
+      }
+    }
+    let (mut ft, mut g, mut flat) = (vec![], vec![], vec![]);
+    for (fi, f) in idx.iter().enumerate() {
+      flat.push((0..f.len()).map(|ti| {
+        ft.push((fi, ti));
+        g.push(f[ti].map(|i| ids[i as usize]));
+        g.len() - 1
+      }).collect());
+    }
+    Operand{ft, g, flat}
+  }
+}
+
+/// vertex of the split triangle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Vert {
+  /// corner of the triangle
+  Corner(usize),
+  /// global point (welded pierce point or vertex of the other)
+  Point(usize)
+}
+
+/// 2D projection axes keeping counter clockwise
+fn axes(c: &[[f64; 3]; 3]) -> (usize, usize) {
+  let n = cross_f3(&sub_f3(&c[1], &c[0]), &sub_f3(&c[2], &c[0]));
+  let k = if n[0].abs() >= n[1].abs() && n[0].abs() >= n[2].abs() { 0 }
+    else if n[1].abs() >= n[2].abs() { 1 } else { 2 };
+  match n[k] >= 0.0 {
+  true => ((k + 1) % 3, (k + 2) % 3),
+  false => ((k + 2) % 3, (k + 1) % 3)
+  }
+}
+
+/// split the triangle along the segments (regions of triangles)
+/// - g: global ids of the corners
+/// - lc: welded global ids of the corners
+/// - pos: position of the global point
+/// - on: points on the edges (sorted global ids)
+/// - segs: segments (global points)
+fn split(g: &[usize; 3], lc: &[usize; 3], pos: &dyn Fn(usize) -> [f64; 3],
+  on: &HashMap<(usize, usize), Vec<usize>>, segs: &[(usize, usize)]) ->
+  Vec<Vec<[Vert; 3]>> {
+  let c = g.map(pos);
+  let (ax, ay) = axes(&c);
+  let mut lv = (0..3).map(|j| (Vert::Corner(j), [c[j][ax], c[j][ay]]))
+    .collect::<Vec<_>>();
+  let mut ids = (0..3).map(|j| (lc[j], j)).collect::<HashMap<_, _>>();
+  let mut local = |k: usize| *ids.entry(k).or_insert_with(|| {
+    let q = pos(k);
+    lv.push((Vert::Point(k), [q[ax], q[ay]]));
+    lv.len() - 1
+  });
+  let mut es = vec![];
+  for &(a, b) in segs.iter() { es.push((local(a), local(b))); }
+  for j in 0..3 {
+    let (a, b) = (g[j], g[(j + 1) % 3]);
+    let e = (a.min(b), a.max(b));
+    let d = sub_f3(&c[(j + 1) % 3], &c[j]);
+    let mut pe = on.get(&e).map_or(vec![], |ps| ps.iter().map(|&k| {
+      let t = dot_f3(&d, &sub_f3(&pos(k), &c[j]));
+      (t, local(k))
+    }).collect::<Vec<_>>());
+    pe.sort_by(|p, q| p.0.partial_cmp(&q.0)
+      .unwrap_or(std::cmp::Ordering::Equal));
+    let mut chain = vec![j];
+    chain.extend(pe.iter().map(|&(_, k)| k));
+    chain.push((j + 1) % 3);
+    chain.dedup();
+    es.extend(chain.windows(2).map(|w| (w[0], w[1])));
+  }
+  let n = lv.len();
+  let mut out = vec![vec![]; n];
+  for &(a, b) in es.iter() {
+    if a == b || out[a].contains(&b) { continue; }
+    out[a].push(b);
+    out[b].push(a);
+  }
+  let p2 = lv.iter().map(|v| v.1).collect::<Vec<_>>();
+  for (a, o) in out.iter_mut().enumerate() {
+    let ang = |b: &usize| (p2[*b][1] - p2[a][1]).atan2(p2[*b][0] - p2[a][0]);
+    o.sort_by(|p, q| ang(p).partial_cmp(&ang(q))
+      .unwrap_or(std::cmp::Ordering::Equal));
+  }
+  let mut seen = HashMap::<(usize, usize), bool>::new();
+  let mut cycles = vec![];
+  for a in 0..n {
+    for &b in out[a].iter() {
+      if seen.contains_key(&(a, b)) { continue; }
+      let mut cy = vec![];
+      let (mut u, mut v) = (a, b);
+      while seen.insert((u, v), true).is_none() {
+        cy.push(u);
+        let o = &out[v];
+        let i = o.iter().position(|&w| w == u).unwrap();
+        let w = o[(i + o.len() - 1) % o.len()]; // next clockwise from v u
+        (u, v) = (v, w);
+      }
+      cycles.push(cy);
+    }
+  }
+  let mut cs = cycles.iter().map(|cy| cy.iter().map(|&k| p2[k]).collect())
+    .collect::<Vec<Vec<_>>>();
+  let area = cs.iter().map(|c| polygon_area(c)).collect::<Vec<_>>();
+  if let Some(k) = (0..cs.len()).min_by(|&i, &j| area[i].partial_cmp(&area[j])
+    .unwrap_or(std::cmp::Ordering::Equal)) { cs[k].clear(); } // outside
+  group_contours(&cs).into_iter().map(|(o, hs)| {
+    let all = [o].iter().chain(hs.iter())
+      .flat_map(|&k| cycles[k].iter().cloned()).collect::<Vec<_>>();
+    let holes = hs.iter().map(|&h| cs[h].clone()).collect::<Vec<_>>();
+    triangulate(&cs[o], &holes).into_iter()
+      .map(|t| t.map(|i| lv[all[i as usize]].0)).collect()
+  }).collect()
+}
+
+/// side of the fragment to the other operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+  /// inside
+  In,
+  /// outside
+  Out,
+  /// on the face of the same direction
+  OnSame,
+  /// on the face of the opposite direction
+  OnOpp
+}
+
+/// keep the fragment of a or b (Some(flip) when kept)
+fn keep(op: CsgOp, of_b: bool, s: Side) -> Option<bool> {
+  match (op, of_b, s) {
+  (CsgOp::Union, _, Side::Out) | (CsgOp::Union, false, Side::OnSame) |
+  (CsgOp::Intersection, _, Side::In) |
+  (CsgOp::Intersection, false, Side::OnSame) |
+  (CsgOp::Difference, false, Side::Out) |
+  (CsgOp::Difference, false, Side::OnOpp) |
+  (CsgOp::SymmetricDifference, _, Side::Out) => Some(false),
+  (CsgOp::Difference, true, Side::In) |
+  (CsgOp::SymmetricDifference, _, Side::In) => Some(true),
+  _ => None
+  }
+}
+
+/// side of the point q on the fragment of the normal n
+/// (on the face when the distance is within p, else by the winding number)
+fn side<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]],
+  bvh: &Bvh<F>, q: &[f64; 3], n: &[f64; 3], p: F) -> Side {
+  let qf = q.map(|x| <F>::from(x).unwrap());
+  if let Some(h) = closest_point(idx, &[], vtx, Some(bvh), &qf)
+    .filter(|h| h.t <= p) {
+    let c = idx[h.fi][h.ti].map(|i|
+      vtx[i as usize].map(|x| x.to_f64().unwrap()));
+    let m = cross_f3(&sub_f3(&c[1], &c[0]), &sub_f3(&c[2], &c[0]));
+    return if dot_f3(&m, n) > 0.0 { Side::OnSame } else { Side::OnOpp };
+  }
+  match winding_number(idx, vtx, Some(bvh), &qf) > <F>::from(0.5).unwrap() {
+  true => Side::In,
+  false => Side::Out
+  }
+}
+
+/// barycentrics of q in the triangle (2D projection)
+fn bary(c: &[[f64; 3]; 3], q: &[f64; 3]) -> [f64; 3] {
+  let (ax, ay) = axes(c);
+  let p = c.map(|v| [v[ax], v[ay]]);
+  let cr = |a: &[f64; 2], b: &[f64; 2], c: &[f64; 2]|
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+  let q = [q[ax], q[ay]];
+  let s = cr(&p[0], &p[1], &p[2]);
+  if s == 0.0 { return [1.0, 0.0, 0.0]; }
+  [cr(&q, &p[1], &p[2]) / s, cr(&p[0], &q, &p[2]) / s, cr(&p[0], &p[1], &q) / s]
+}
+
+/// CSG of the closed polyhedrons (Degenerate when an operand is not valid)
+/// - a: operand a
+/// - b: operand b
+/// - op: operation
+/// - p: precision for equality (welds vertices and intersection points)
+pub fn csg<F: Float + std::fmt::Debug + std::iter::Sum,
+  A: TUV<F> + ?Sized, B: TUV<F> + ?Sized>(a: &A, b: &B, op: CsgOp, p: F) ->
+  Result<Polyhedron<F>, PHError> {
+  validate(a.ref_tri(), a.ref_vtx(), p).check()?;
+  validate(b.ref_tri(), b.ref_vtx(), p).check()?;
+  let (na, nb) = (a.ref_vtx().len() as u32, b.ref_vtx().len() as u32);
+  let (mut pts, mut src) = (vec![], vec![]);
+  let oa = Operand::new(a.ref_tri(), a.ref_vtx(), p, 0, &mut pts, &mut src);
+  let ob = Operand::new(b.ref_tri(), b.ref_vtx(), p, na, &mut pts, &mut src);
+  let mut pc = Pierce::new(pts);
+  let pf = p.to_f64().unwrap();
+  let np = pc.pts.len();
+  let (ba, bb) = (Bvh::from_polyhedron(a), Bvh::from_polyhedron(b));
+  let mut sgs = vec![]; // (on b, flat triangle, global ids of the ends)
+  for (ka, ga) in oa.g.iter().enumerate() {
+    let c = ga.map(|i| pc.pts[i]);
+    let lo = [0, 1, 2].map(|j| c[0][j].min(c[1][j]).min(c[2][j]) - pf);
+    let hi = [0, 1, 2].map(|j| c[0][j].max(c[1][j]).max(c[2][j]) + pf);
+    let mut stack = if bb.nodes.is_empty() { vec![] } else { vec![0] };
+    while let Some(k) = stack.pop() {
+      let nd = &bb.nodes[k];
+      if (0..3).any(|j| nd.min[j].to_f64().unwrap() > hi[j]
+        || nd.max[j].to_f64().unwrap() < lo[j]) { continue; }
+      let Some([l, r]) = nd.children else {
+        for &(fi, ti) in bb.tris[nd.range.0..nd.range.1].iter() {
+          let kb = ob.flat[fi][ti];
+          let gb = ob.g[kb];
+          let mut ends = vec![];
+          for j in 0..3 {
+            let (u, v) = (ga[j], ga[(j + 1) % 3]);
+            ends.extend(pc.get((u.min(v), u.max(v)), (true, kb), &gb));
+            let (u, v) = (gb[j], gb[(j + 1) % 3]);
+            ends.extend(pc.get((u.min(v), u.max(v)), (false, ka), ga));
+          }
+          if ends.len() == 2 {
+            sgs.push((false, ka, ends[0], ends[1]));
+            sgs.push((true, kb, ends[0], ends[1]));
+          }
+          if pc.is_coplanar(ga, &gb, pf) {
+            sgs.extend(pc.coplanar(ga, &gb).into_iter().map(|(on_b, s, e)|
+              (on_b, if on_b { kb } else { ka }, s, e)));
+          }
+        }
+        continue;
+      };
+      stack.push(l);
+      stack.push(r);
+    }
+  }
+  // weld the pierce points (to each other or to the vertices)
+  let m = merge_vertices(&pc.pts.iter().chain(pc.pp.iter())
+    .map(|q| q.map(|x| <F>::from(x).unwrap())).collect::<Vec<_>>(), p);
+  let loc = |k: usize| m[k] as usize; // also welds the vertices of a and b
+  let pos = |k: usize| if k < np { pc.pts[k] } else { pc.pp[k - np] };
+  let mut on = HashMap::<(usize, usize), Vec<usize>>::new(); // edge points
+  for &(k, e) in pc.on.iter() { on.entry(e).or_default().push(loc(k)); }
+  for &(k, t) in pc.near.iter() {
+    for j in 0..3 {
+      let (u, v) = (t[j], t[(j + 1) % 3]);
+      if dist_seg(&pos(k), &pos(u), &pos(v)) <= pf {
+        on.entry((u.min(v), u.max(v))).or_default().push(loc(k));
+      }
+    }
+  }
+  for (e, ps) in on.iter_mut() {
+    ps.retain(|&r| r != loc(e.0) && r != loc(e.1));
+    ps.sort();
+    ps.dedup();
+  }
+  let mut segs = [vec![vec![]; oa.g.len()], vec![vec![]; ob.g.len()]];
+  for &(on_b, k, s, e) in sgs.iter() {
+    let (s, e) = (loc(s), loc(e));
+    let sg: &mut Vec<(usize, usize)> = &mut segs[on_b as usize][k];
+    if s != e && !sg.contains(&(s, e)) && !sg.contains(&(e, s)) {
+      sg.push((s, e));
+    }
+  }
+  let mut vtx = a.ref_vtx().iter().chain(b.ref_vtx().iter()).cloned()
+    .chain(pc.pp.iter().map(|q| q.map(|x| <F>::from(x).unwrap())))
+    .collect::<Vec<_>>();
+  let with_uv = !a.ref_uv().is_empty() || !b.ref_uv().is_empty();
+  let nf = a.ref_tri().len();
+  let mut tri = vec![vec![]; nf + b.ref_tri().len()];
+  let mut uv = vec![vec![]; if with_uv { tri.len() } else { 0 }];
+  for (of_b, o) in [(false, &oa), (true, &ob)] {
+    let (idx, suv, off) = match of_b {
+    false => (a.ref_tri(), a.ref_uv(), 0),
+    true => (b.ref_tri(), b.ref_uv(), na)
+    };
+    let (oi, ov, obvh) = match of_b {
+    false => (b.ref_tri(), b.ref_vtx(), &bb),
+    true => (a.ref_tri(), a.ref_vtx(), &ba)
+    };
+    for (k, g) in o.g.iter().enumerate() {
+      let (fi, ti) = o.ft[k];
+      let t = idx[fi][ti];
+      let c = g.map(|i| pc.pts[i]);
+      let n = cross_f3(&sub_f3(&c[1], &c[0]), &sub_f3(&c[2], &c[0]));
+      let sg = &segs[of_b as usize][k];
+      let regions = match sg.is_empty() && (0..3).all(|j| {
+        let (u, v) = (g[j], g[(j + 1) % 3]);
+        on.get(&(u.min(v), u.max(v))).is_none_or(|ps| ps.is_empty())
+      }) {
+      true => vec![vec![[0, 1, 2].map(Vert::Corner)]],
+      false => split(g, &g.map(loc), &pos, &on, sg)
+      };
+      let vp = |v: &Vert| match *v {
+      Vert::Corner(j) => c[j],
+      Vert::Point(q) => pos(q)
+      };
+      for r in regions.iter() {
+        let Some(f) = r.first() else { continue; };
+        let q = [0, 1, 2].map(|j|
+          f.iter().map(|v| vp(v)[j]).sum::<f64>() / 3.0);
+        let Some(flip) = keep(op, of_b, side(oi, ov, obvh, &q, &n, p)) else {
+          continue; };
+        let fo = fi + if of_b { nf } else { 0 };
+        for tv in r.iter() {
+          let mut ids = tv.map(|v| match v {
+          Vert::Corner(j) => off + t[j],
+          Vert::Point(q) if q < np => src[q],
+          Vert::Point(q) => na + nb + (q - np) as u32
+          });
+          let mut w = tv.map(|v| match (!suv.is_empty(), v) {
+          (false, _) => [<F>::from(0).unwrap(); 2],
+          (true, Vert::Corner(j)) => suv[fi][ti][j],
+          (true, Vert::Point(_)) => {
+            let s = bary(&c, &vp(&v));
+            let u = suv[fi][ti];
+            [0, 1].map(|i| u[0][i] * <F>::from(s[0]).unwrap()
+              + u[1][i] * <F>::from(s[1]).unwrap()
+              + u[2][i] * <F>::from(s[2]).unwrap())
+          }
+          });
+          if flip { ids.swap(1, 2); w.swap(1, 2); }
+          tri[fo].push(ids);
+          if with_uv { uv[fo].push(w); }
+        }
+      }
+    }
+  }
+  let mut map = vec![u32::MAX; vtx.len()];
+  let mut vs = vec![];
+  for t in tri.iter_mut().flatten() {
+    for i in t.iter_mut() {
+      if map[*i as usize] == u32::MAX {
+        map[*i as usize] = vs.len() as u32;
+        vs.push(vtx[*i as usize]);
+      }
+      *i = map[*i as usize];
+    }
+  }
+  vtx = vs;
+  let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+  Ok(Polyhedron{vtx, tri, uv, vol, center: false})
+}
+
+/// distance from q to the segment a b
+fn dist_seg(q: &[f64; 3], a: &[f64; 3], b: &[f64; 3]) -> f64 {
+  let (d, w) = (sub_f3(b, a), sub_f3(q, a));
+  let t = (dot_f3(&w, &d) / dot_f3(&d, &d)).clamp(0.0, 1.0);
+  let r = sub_f3(&w, &d.map(|x| x * t));
+  dot_f3(&r, &r).sqrt()
+}
@@ -0,0 +1,80 @@
+//! PHError on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+/// PHError
+#[derive(Debug, Clone, PartialEq)]
+pub enum PHError {
+  /// invalid parameter (name and reason)
+  InvalidParameter(String),
+  /// number of vertices does not fit in the index width (vertices, max)
+  IndexOverflow(u64, u64),
+  /// degenerate geometry (reason)
+  Degenerate(String)
+}
+
+/// Display for PHError
+impl std::fmt::Display for PHError {
+  /// fmt
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+    PHError::InvalidParameter(s) => write!(f, "invalid parameter: {}", s),
+    PHError::IndexOverflow(n, m) =>
+      write!(f, "index overflow: {} vertices (max {})", n, m),
+    PHError::Degenerate(s) => write!(f, "degenerate geometry: {}", s)
+    }
+  }
+}
+
+/// Error for PHError
+impl std::error::Error for PHError {}
+
+/// check parameter is finite and positive
+pub fn check_positive<F: Float>(name: &str, v: F) -> Result<F, PHError> {
+  if v.is_finite() && v > <F>::from(0).unwrap() { return Ok(v); }
+  Err(PHError::InvalidParameter(
+    format!("{} must be finite and positive", name)))
+}
+
+/// check parameter is finite and not negative
+pub fn check_non_negative<F: Float>(name: &str, v: F) -> Result<F, PHError> {
+  if v.is_finite() && v >= <F>::from(0).unwrap() { return Ok(v); }
+  Err(PHError::InvalidParameter(format!("{} must be finite and >= 0", name)))
+}
+
+/// check quality is not zero
+pub fn check_quality(name: &str, q: u16) -> Result<u16, PHError> {
+  if q > 0 { return Ok(q); }
+  Err(PHError::InvalidParameter(format!("{} must be > 0", name)))
+}
+
+/// check number of sides of the polygon is 3 or more
+pub fn check_sides(name: &str, n: u16) -> Result<u16, PHError> {
+  if n >= 3 { return Ok(n); }
+  Err(PHError::InvalidParameter(format!("{} must be >= 3", name)))
+}
+
+/// check volume is finite and positive (closed and counter clockwise)
+pub fn check_volume<F: Float>(vol: F) -> Result<F, PHError> {
+  if vol.is_finite() && vol > <F>::from(0).unwrap() { return Ok(vol); }
+  Err(PHError::Degenerate("volume must be finite and positive".to_string()))
+}
+
+/// check number of vertices fits in the index u32
+pub fn check_index(n: u64) -> Result<u32, PHError> {
+  let m = u32::MAX as u64 + 1; // index 0 to u32::MAX
+  if n > m { Err(PHError::IndexOverflow(n, m)) } else { Ok(n as u32) }
+}
+
+/// down conversion index u32 to u16
+pub fn tri_u16(idx: &[Vec<[u32; 3]>]) ->
+  Result<Vec<Vec<[u16; 3]>>, PHError> {
+  let n = idx.iter().flat_map(|f| f.iter().flat_map(|t| t.iter()))
+    .max().map_or(0, |&i| i as u64 + 1);
+  let m = u16::MAX as u64 + 1; // index 0 to u16::MAX
+  if n > m { return Err(PHError::IndexOverflow(n, m)); }
+  Ok(idx.iter().map(|f|
+    f.iter().map(|t| t.map(|i| i as u16)).collect()
+  ).collect())
+}
@@ -0,0 +1,193 @@
+//! Convex hull (quickhull) on polyhedron faces for Rust
+//!
+//! coplanar triangles of the hull are merged into the one face
+//!
+
+use std::collections::{HashMap, VecDeque};
+
+use num::Float;
+
+use crate::{merge_vertices, calc_cg_with_volume};
+use crate::{sub_f3, cross_f3, dot_f3};
+use crate::polyhedron::Polyhedron;
+use crate::error::PHError;
+
+/// Plane of the hull triangle
+#[derive(Debug, Clone)]
+struct Face<F: Float> {
+  /// vertex ids (counter clockwise from outside)
+  v: [usize; 3],
+  /// unit normal
+  n: [F; 3],
+  /// distance from the origin
+  d: F,
+  /// points outside of this face
+  outside: Vec<usize>,
+  /// false when removed
+  alive: bool
+}
+
+/// Face
+impl<F: Float> Face<F> {
+  /// construct
+  fn new(v: [usize; 3], vtx: &[[F; 3]]) -> Self {
+    let [a, b, c] = v.map(|i| vtx[i]);
+    let n = cross_f3(&sub_f3(&b, &a), &sub_f3(&c, &a));
+    let l = dot_f3(&n, &n).sqrt();
+    let n = n.map(|x| x / l);
+    Face{v, n, d: dot_f3(&n, &a), outside: vec![], alive: true}
+  }
+  /// signed distance of the point
+  fn dist(&self, q: &[F; 3]) -> F {
+    dot_f3(&self.n, q) - self.d
+  }
+}
+
+/// convex hull (quickhull)
+/// - vtx: points
+/// - p: precision for equality (welds points, thickness of the faces)
+pub fn convex_hull<F: Float + std::fmt::Debug + std::iter::Sum>(
+  vtx: &[[F; 3]], p: F) -> Result<Polyhedron<F>, PHError> {
+  let m = merge_vertices(vtx, p);
+  let pts = m.iter().enumerate().filter(|&(i, &k)| i == k as usize)
+    .map(|(i, _)| vtx[i]).collect::<Vec<_>>();
+  let mut faces = simplex(&pts, p)?;
+  let sv = faces.iter().flat_map(|f| f.v).collect::<Vec<_>>();
+  let rest = (0..pts.len()).filter(|i| !sv.contains(i)).collect::<Vec<_>>();
+  assign(&mut faces, 0, rest, &pts, p);
+  let mut em = HashMap::<(usize, usize), usize>::new(); // directed edge
+  for (k, f) in faces.iter().enumerate() {
+    for j in 0..3 { em.insert((f.v[j], f.v[(j + 1) % 3]), k); }
+  }
+  let mut stack = pending(&faces, 0); // faces with outside points
+  while let Some(s) = stack.pop() {
+    if !faces[s].alive || faces[s].outside.is_empty() { continue; }
+    let eye = *faces[s].outside.iter().max_by(|&&a, &&b|
+      faces[s].dist(&pts[a]).partial_cmp(&faces[s].dist(&pts[b]))
+      .unwrap_or(std::cmp::Ordering::Equal)).unwrap();
+    let q = pts[eye];
+    let mut visible = vec![s];
+    let mut seen = vec![false; faces.len()];
+    seen[s] = true;
+    let mut horizon = vec![];
+    let mut bfs = VecDeque::from([s]);
+    while let Some(k) = bfs.pop_front() {
+      for j in 0..3 {
+        let e = (faces[k].v[j], faces[k].v[(j + 1) % 3]);
+        let n = em[&(e.1, e.0)];
+        if seen[n] { continue; }
+        if faces[n].dist(&q) > p {
+          seen[n] = true;
+          visible.push(n);
+          bfs.push_back(n);
+        } else {
+          horizon.push(e);
+        }
+      }
+    }
+    let mut orphan = vec![];
+    for &k in visible.iter() {
+      faces[k].alive = false;
+      orphan.append(&mut faces[k].outside);
+      for j in 0..3 { em.remove(&(faces[k].v[j], faces[k].v[(j + 1) % 3])); }
+    }
+    let n0 = faces.len();
+    for (a, b) in horizon {
+      let k = faces.len();
+      faces.push(Face::new([a, b, eye], &pts));
+      for e in [(a, b), (b, eye), (eye, a)] { em.insert(e, k); }
+    }
+    orphan.retain(|&i| i != eye);
+    assign(&mut faces, n0, orphan, &pts, p);
+    stack.extend(pending(&faces, n0));
+  }
+  let hull = faces.into_iter().filter(|f| f.alive).collect::<Vec<_>>();
+  Ok(to_polyhedron(&hull, &pts, p))
+}
+
+/// initial tetrahedron (Degenerate when all points are coplanar)
+fn simplex<F: Float>(pts: &[[F; 3]], p: F) -> Result<Vec<Face<F>>, PHError> {
+  let err = |s: &str| Err(PHError::Degenerate(format!("convex hull {}", s)));
+  if pts.len() < 4 { return err("needs 4 or more points"); }
+  let ext = (0..3).map(|j| {
+    let (mut lo, mut hi) = (0, 0);
+    for (i, v) in pts.iter().enumerate() {
+      if v[j] < pts[lo][j] { lo = i; }
+      if v[j] > pts[hi][j] { hi = i; }
+    }
+    (pts[hi][j] - pts[lo][j], lo, hi)
+  }).collect::<Vec<_>>();
+  let &(w, i0, i1) = ext.iter().max_by(|a, b|
+    a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal)).unwrap();
+  if w <= p { return err("points are coincident"); }
+  let farthest = |f: &dyn Fn(&[F; 3]) -> F| (0..pts.len())
+    .map(|i| (f(&pts[i]), i)).fold((<F>::from(0).unwrap(), 0), |a, b|
+      if b.0 > a.0 { b } else { a });
+  let u = sub_f3(&pts[i1], &pts[i0]);
+  let (d2, i2) = farthest(&|q: &[F; 3]| {
+    let c = cross_f3(&u, &sub_f3(q, &pts[i0]));
+    dot_f3(&c, &c).sqrt() / w
+  });
+  if d2 <= p { return err("points are collinear"); }
+  let n = Face::new([i0, i1, i2], pts);
+  let (d3, i3) = farthest(&|q: &[F; 3]| n.dist(q).abs());
+  if d3 <= p { return err("points are coplanar"); }
+  let (i1, i2) = if n.dist(&pts[i3]) > <F>::from(0).unwrap() {
+    (i2, i1) } else { (i1, i2) };
+  Ok([[i0, i1, i2], [i0, i3, i1], [i1, i3, i2], [i2, i3, i0]].iter()
+    .map(|&v| Face::new(v, pts)).collect())
+}
+
+/// ids of the faces from n0 which have outside points
+fn pending<F: Float>(faces: &[Face<F>], n0: usize) -> Vec<usize> {
+  (n0..faces.len()).filter(|&k| !faces[k].outside.is_empty()).collect()
+}
+
+/// assign the points to the outside of the first face from n0
+/// (points inside of all faces are dropped)
+fn assign<F: Float>(faces: &mut [Face<F>], n0: usize, ps: Vec<usize>,
+  pts: &[[F; 3]], p: F) {
+  for i in ps {
+    if let Some(f) = faces[n0..].iter_mut().find(|f|
+      f.alive && f.dist(&pts[i]) > p) { f.outside.push(i); }
+  }
+}
+
+/// polyhedron with the coplanar triangles merged into the one face
+fn to_polyhedron<F: Float + std::fmt::Debug + std::iter::Sum>(
+  hull: &[Face<F>], pts: &[[F; 3]], p: F) -> Polyhedron<F> {
+  let mut ids = vec![u32::MAX; pts.len()];
+  let mut vtx = vec![];
+  for f in hull.iter() {
+    for &i in f.v.iter() {
+      if ids[i] == u32::MAX { ids[i] = vtx.len() as u32; vtx.push(pts[i]); }
+    }
+  }
+  let mut em = HashMap::<(usize, usize), usize>::new();
+  for (k, f) in hull.iter().enumerate() {
+    for j in 0..3 { em.insert((f.v[j], f.v[(j + 1) % 3]), k); }
+  }
+  let mut done = vec![false; hull.len()];
+  let mut tri = vec![];
+  for s in 0..hull.len() {
+    if done[s] { continue; }
+    done[s] = true;
+    let mut group = vec![];
+    let mut bfs = VecDeque::from([s]);
+    while let Some(k) = bfs.pop_front() {
+      group.push(hull[k].v.map(|i| ids[i]));
+      for j in 0..3 {
+        let n = em[&(hull[k].v[(j + 1) % 3], hull[k].v[j])];
+        if done[n] || dot_f3(&hull[n].n, &hull[s].n) <= <F>::from(0).unwrap()
+        || hull[n].v.iter().any(|&i| hull[s].dist(&pts[i]).abs() > p) {
+          continue;
+        }
+        done[n] = true;
+        bfs.push_back(n);
+      }
+    }
+    tri.push(group);
+  }
+  let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+  Polyhedron{vtx, tri, uv: vec![], vol, center: false}
+}
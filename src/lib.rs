@@ -2,68 +2,104 @@
 //! polyhedron faces for Rust
 //!
 
+pub mod error;
+pub use error::PHError;
 pub mod polyhedron;
 pub use polyhedron::*;
+pub mod obj;
+pub mod stl;
+pub mod normal;
+pub mod mass;
+pub mod conway;
+pub mod subdivision;
+pub mod weld;
+pub mod validate;
+pub mod query;
+pub mod hull;
+pub mod triangulate;
+pub mod slice;
+pub mod csg;
 
 use num::Float;
-// use qm::v::TVector;
-use qm::m::{TMatrix, m3::Matrix3};
+use qm::v::{TVector, v4::Vector4};
+use qm::m::{TMatrix, m3::Matrix3, m4::Matrix4};
 pub use qm::{prec_eq, prec_eq_f};
 
 /// sum of vec [F; 2] without trait Sum
 /// when use += need trait Float + std::ops::AddAssign &lt; [F; 2] &gt;
-pub fn sum_f2<F: Float>(uvs: &Vec<[F; 2]>) -> Vec<F> {
+pub fn sum_f2<F: Float>(uvs: &[[F; 2]]) -> Vec<F> {
   uvs.iter().fold(vec![<F>::from(0).unwrap(); 2], |s, p|
     s.iter().zip(p.iter()).map(|(&q, &p)| q + p).collect())
 }
 
 /// avg of vec [F; 2]
-pub fn avg_f2<F: Float>(uvs: &Vec<[F; 2]>) -> Vec<F> {
+pub fn avg_f2<F: Float>(uvs: &[[F; 2]]) -> Vec<F> {
   let n = <F>::from(uvs.len()).unwrap();
   sum_f2(uvs).iter().map(|&p| p / n).collect()
 }
 
 /// center indexed uv [F; 2]
-pub fn center_indexed_uv<F: Float>(idx: &[u16], uvs: &Vec<[F; 2]>) -> [F; 2] {
-  let p = avg_f2(&idx.iter().map(|&i| uvs[i as usize]).collect());
+pub fn center_indexed_uv<F: Float>(idx: &[u32], uvs: &[[F; 2]]) -> [F; 2] {
+  let p = avg_f2(&idx.iter().map(|&i| uvs[i as usize]).collect::<Vec<_>>());
   p.as_slice().try_into().unwrap()
 }
 
+/// uv triangles (parallel to idx) from uv on each vertex
+pub fn uv_indexed<F: Float>(idx: &[Vec<[u32; 3]>], uvs: &[[F; 2]]) ->
+  Vec<Vec<[[F; 2]; 3]>> {
+  idx.iter().map(|f|
+    f.iter().map(|t| t.map(|i| uvs[i as usize])).collect()
+  ).collect()
+}
+
+/// uv on the cap disc of the one texture (lower half: bottom left, top right)
+/// - th: angle around Y axis (0 at +Z)
+/// - k: 0 to 1 (center to rim)
+/// - top: false: bottom (seen from below), true: top (seen from above)
+pub fn cap_uv<F: Float>(th: f64, k: f64, top: bool) -> [F; 2] {
+  let (u, w) = (0.25 * k * th.sin(), 0.25 * k * th.cos());
+  let uv = match top {
+  true => [0.75 + u, 0.75 + w],
+  false => [0.25 + u, 0.75 - w]
+  };
+  [<F>::from(uv[0]).unwrap(), <F>::from(uv[1]).unwrap()]
+}
+
 /// sum of vec [F; 3] without trait Sum
 /// when use += need trait Float + std::ops::AddAssign &lt; [F; 3] &gt;
-pub fn sum_f3<F: Float>(vs: &Vec<[F; 3]>) -> Vec<F> {
+pub fn sum_f3<F: Float>(vs: &[[F; 3]]) -> Vec<F> {
   vs.iter().fold(vec![<F>::from(0).unwrap(); 3], |s, p|
     s.iter().zip(p.iter()).map(|(&q, &p)| q + p).collect())
 }
 
 /// avg of vec [F; 3]
-pub fn avg_f3<F: Float>(vs: &Vec<[F; 3]>) -> Vec<F> {
+pub fn avg_f3<F: Float>(vs: &[[F; 3]]) -> Vec<F> {
   let n = <F>::from(vs.len()).unwrap();
   sum_f3(vs).iter().map(|&v| v / n).collect()
 }
 
 /// center indexed [F; 3]
-pub fn center_indexed<F: Float>(idx: &[u16], vtx: &Vec<[F; 3]>) -> [F; 3] {
-  let p = avg_f3(&idx.iter().map(|&i| vtx[i as usize]).collect());
+pub fn center_indexed<F: Float>(idx: &[u32], vtx: &[[F; 3]]) -> [F; 3] {
+  let p = avg_f3(&idx.iter().map(|&i| vtx[i as usize]).collect::<Vec<_>>());
   p.as_slice().try_into().unwrap()
 }
 
 /// sum of vec [F; 4] without trait Sum
 /// when use += need trait Float + std::ops::AddAssign &lt; [F; 4] &gt;
-pub fn sum_f4<F: Float>(vs: &Vec<[F; 4]>) -> Vec<F> {
+pub fn sum_f4<F: Float>(vs: &[[F; 4]]) -> Vec<F> {
   vs.iter().fold(vec![<F>::from(0).unwrap(); 4], |s, p|
     s.iter().zip(p.iter()).map(|(&q, &p)| q + p).collect())
 }
 
 /// avg of vec [F; 4]
-pub fn avg_f4<F: Float>(vs: &Vec<[F; 4]>) -> Vec<F> {
+pub fn avg_f4<F: Float>(vs: &[[F; 4]]) -> Vec<F> {
   let n = <F>::from(vs.len()).unwrap();
   sum_f4(vs).iter().map(|&v| v / n).collect()
 }
 
 /// center indexed [F; 4]
-pub fn center_indexed_f4<F: Float>(idx: &[u16], vtx: &Vec<[F; 4]>) -> [F; 4] {
-  let p = avg_f4(&idx.iter().map(|&i| vtx[i as usize]).collect());
+pub fn center_indexed_f4<F: Float>(idx: &[u32], vtx: &[[F; 4]]) -> [F; 4] {
+  let p = avg_f4(&idx.iter().map(|&i| vtx[i as usize]).collect::<Vec<_>>());
   p.as_slice().try_into().unwrap()
 }
 
@@ -81,6 +117,35 @@ pub fn divide_ext<F: Float>(p: &[F; 3], q: &[F; 3], m: i32, n: i32) -> [F; 3] {
   divide_int(p, q, m, -n)
 }
 
+/// sub of [F; 3]
+pub fn sub_f3<F: Float>(a: &[F; 3], b: &[F; 3]) -> [F; 3] {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// cross of [F; 3]
+pub fn cross_f3<F: Float>(a: &[F; 3], b: &[F; 3]) -> [F; 3] {
+  [
+    a[1] * b[2] - a[2] * b[1],
+    a[2] * b[0] - a[0] * b[2],
+    a[0] * b[1] - a[1] * b[0]]
+}
+
+/// dot of [F; 3]
+pub fn dot_f3<F: Float>(a: &[F; 3], b: &[F; 3]) -> F {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// norm of [F; 3]
+pub fn norm_f3<F: Float>(a: &[F; 3]) -> F {
+  dot_f3(a, a).sqrt()
+}
+
+/// unit of [F; 3] (NaN when the norm is 0)
+pub fn unit_f3<F: Float>(a: &[F; 3]) -> [F; 3] {
+  let l = norm_f3(a);
+  a.map(|x| x / l)
+}
+
 /// solve quadratic equation
 pub fn solve<F: Float>(a: F, b: F, c: F) -> [F; 2] {
   let z = <F>::from(2).unwrap();
@@ -98,27 +163,25 @@ pub fn solve2<F: Float>(a: F, b2: F, c: F) -> [F; 2] {
 
 /// sol
 pub fn sol<F: Float>(r: &[F], s: F, e: F) -> F {
-  for i in 0..r.len() {
-    if r[i] >= s && r[i] <= e { return r[i]; }
+  for &x in r.iter() {
+    if x >= s && x <= e { return x; }
   }
   <F>::from(0).unwrap()
 }
 
 /// calc cg f2 x axis
-pub fn calc_cg_f2_x<F: Float>(vs: &Vec<[F; 2]>) -> Vec<F> {
+pub fn calc_cg_f2_x<F: Float>(vs: &[[F; 2]]) -> Vec<F> {
   let o = <F>::from(0).unwrap();
   let z = <F>::from(2).unwrap();
-  let mut stk = (1..vs.len()).into_iter().map(|vn| {
+  let mut stk = (1..vs.len()).map(|vn| {
     let (a, b) = (vs[vn - 1], vs[vn]);
     [(b[0] - a[0]) * (b[1] + a[1]) / z, o]
   }).collect::<Vec<_>>();
   stk.push([o, o]);
-  for vn in 0..vs.len() {
-    stk[vn][1] = if vn == 0 { o } else { stk[vn - 1][0] + stk[vn - 1][1] };
-  }
+  let mut acc = o;
+  for s in stk.iter_mut() { s[1] = acc; acc = acc + s[0]; }
   let middle = stk[stk.len() - 1][1] / z;
-  let mut n = 0;
-  for vn in 0..vs.len() { if stk[vn][1] >= middle { n = vn; break; } }
+  let n = stk.iter().position(|s| s[1] >= middle).unwrap_or(0);
   let m = (middle - stk[n - 1][1]) / (stk[n][1] - stk[n - 1][1]);
   let (h0, h1) = (vs[n - 1][0], vs[n][0]);
   let (w0, w1) = (vs[n - 1][1], vs[n][1]);
@@ -132,30 +195,170 @@ pub fn calc_cg_f2_x<F: Float>(vs: &Vec<[F; 2]>) -> Vec<F> {
 /// (depends on density of vertices because no care of mass volume)
 /// - vs: length &ge; 3
 /// - p: precision for equality
-pub fn calc_cg_f3<F: Float>(vs: &Vec<[F; 3]>, p: F) -> Vec<F> {
-  let mut vtmp = Vec::<[F; 3]>::new();
+pub fn calc_cg_f3<F: Float>(vs: &[[F; 3]], p: F) -> Vec<F> {
+  let m = merge_vertices(vs, p);
+  let vtmp = vs.iter().enumerate().filter(|&(i, _)| m[i] == i as u32)
+    .map(|(_, v)| *v).collect::<Vec<_>>();
+  round_prec(&avg_f3(&vtmp), p, <F>::from(0).unwrap()) // not accurate
+}
+
+/// merge vertices (index of the first coincident vertex for each vertex)
+/// spatial hash on the grid of p (coincident vertices are in the 27 cells)
+/// - vs: vertices
+/// - p: precision for equality
+pub fn merge_vertices<F: Float>(vs: &[[F; 3]], p: F) -> Vec<u32> {
+  if !(p.is_finite() && p > <F>::from(0).unwrap()) { // never prec_eq
+    return (0..vs.len() as u32).collect();
+  }
+  let cell = |v: &[F; 3]| v.map(|x| (x / p).floor().to_i64()
+    .unwrap_or(if x > <F>::from(0).unwrap() { i64::MAX } else { i64::MIN }));
+  let mut grid = std::collections::HashMap::<[i64; 3], Vec<u32>>::new();
+  let mut m = Vec::<u32>::with_capacity(vs.len());
   for i in 0..vs.len() {
-    let mut found = false;
-    for v in vtmp.iter() { if prec_eq(v, p, &vs[i]) { found = true; break; } }
-    if !found { vtmp.push(vs[i]); }
+    let c = cell(&vs[i]);
+    let mut j = None; // the first coincident vertex (same as linear search)
+    for d in 0..27 {
+      let k = [d % 3, d / 3 % 3, d / 9].map(|o| o as i64 - 1);
+      let c = [0, 1, 2].map(|n| c[n].saturating_add(k[n]));
+      let Some(js) = grid.get(&c) else { continue; };
+      let f = js.iter().find(|&&f| prec_eq(&vs[f as usize], p, &vs[i]));
+      if let Some(&f) = f { if j.is_none_or(|g| f < g) { j = Some(f); } }
+    }
+    m.push(j.map_or(i as u32, |j| m[j as usize]));
+    grid.entry(c).or_default().push(i as u32);
   }
-  round_prec(&avg_f3(&vtmp), p, <F>::from(0).unwrap()) // not accurate
+  m
+}
+
+/// fan triangles of each polygon (polygon to the one face)
+pub fn fan_indexed(faces: &[Vec<u32>]) -> Vec<Vec<[u32; 3]>> {
+  faces.iter().map(|f|
+    (1..f.len().saturating_sub(1)).map(|k| [f[0], f[k], f[k + 1]]).collect()
+  ).collect()
+}
+
+/// polyhedron from polygons (each polygon to the one face of fan triangles)
+/// moved to the center of gravity
+pub fn polyhedron_from_polygons<F: Float + std::fmt::Debug + std::iter::Sum>(
+  mut vtx: Vec<[F; 3]>, faces: &[Vec<u32>]) -> Polyhedron<F> {
+  let tri = fan_indexed(faces);
+  let (_cg, vol) = adjust_cg_with_volume(&tri, &mut vtx,
+    <F>::from(1e-6).unwrap());
+  Polyhedron{vtx, tri, uv: vec![], vol, center: false}
+}
+
+/// vertices of the regular n-gon parallel to XZ plane
+/// (counter clockwise seen from +Y)
+/// - r: radius of circumscribed circle
+/// - y: height
+/// - th: angle of the first vertex around Y axis (0 at +Z)
+/// - n: number of vertices
+pub fn polygon_xz<F: Float>(r: F, y: F, th: f64, n: u32) -> Vec<[F; 3]> {
+  (0..n).map(|k| {
+    let t = th + 2.0 * std::f64::consts::PI * k as f64 / n as f64;
+    [r * <F>::from(t.sin()).unwrap(), y, r * <F>::from(t.cos()).unwrap()]
+  }).collect()
+}
+
+/// polygons of the convex polyhedron with the same length edges
+/// (counter clockwise from outside, vertices around the origin)
+pub fn polygons_from_edges(vtx: &[[f64; 3]]) -> Vec<Vec<u32>> {
+  let d = |a: &[f64; 3], b: &[f64; 3]|
+    norm_f3(&[0, 1, 2].map(|k| a[k] - b[k]));
+  let l = (0..vtx.len()).flat_map(|i| (0..i).map(move |j| (i, j)))
+    .map(|(i, j)| d(&vtx[i], &vtx[j])).fold(f64::MAX, f64::min);
+  // neighbours sorted counter clockwise around each vertex (from outside)
+  let adj = vtx.iter().map(|v| {
+    let n = v.map(|p| p / norm_f3(v));
+    let mut ws = (0..vtx.len()).filter(|&j|
+      d(v, &vtx[j]) > 1e-9 && d(v, &vtx[j]) < l * (1.0 + 1e-6))
+      .collect::<Vec<_>>();
+    let e = [0, 1, 2].map(|k| vtx[ws[0]][k] - v[k]);
+    let e1 = [0, 1, 2].map(|k| e[k] - n[k] * (0..3).map(|j| e[j] * n[j])
+      .sum::<f64>());
+    let e2 = [
+      n[1] * e1[2] - n[2] * e1[1],
+      n[2] * e1[0] - n[0] * e1[2],
+      n[0] * e1[1] - n[1] * e1[0]];
+    let th = |j: usize| {
+      let w = [0, 1, 2].map(|k| vtx[j][k] - v[k]);
+      let dt = |a: &[f64; 3]| (0..3).map(|k| w[k] * a[k]).sum::<f64>();
+      dt(&e2).atan2(dt(&e1))
+    };
+    ws.sort_by(|&a, &b| th(a).partial_cmp(&th(b)).unwrap());
+    ws
+  }).collect::<Vec<_>>();
+  let mut used = std::collections::HashSet::<(usize, usize)>::new();
+  let mut faces = vec![];
+  for u in 0..vtx.len() {
+    for &v in adj[u].iter() {
+      if used.contains(&(u, v)) { continue; }
+      let mut f = vec![];
+      let (mut a, mut b) = (u, v);
+      while used.insert((a, b)) {
+        f.push(a as u32);
+        let ws = &adj[b];
+        let i = ws.iter().position(|&w| w == a).unwrap();
+        (a, b) = (b, ws[(i + ws.len() - 1) % ws.len()]); // previous of a
+      }
+      faces.push(f);
+    }
+  }
+  faces
+}
+
+/// calc edges (duplex) vertex id and sorted ids of its neighbour vertices
+/// - idx: index of triangles on each faces
+/// - vtx: vertices
+/// - p: Some(precision) merges coincident vertices, None: index only
+/// - inner: true includes the edges inside a face (shared on the same face)
+pub fn calc_edges<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]],
+  p: Option<F>, inner: bool) -> Vec<(u32, Vec<u32>)> {
+  let m = match p {
+  Some(p) => merge_vertices(vtx, p),
+  None => (0..vtx.len()).map(|i| i as u32).collect()
+  };
+  // (a, b) a &lt; b : (face index, count, on some faces)
+  let mut es = std::collections::BTreeMap::<(u32, u32), (usize, usize, bool)>
+    ::new();
+  for (fi, f) in idx.iter().enumerate() {
+    for t in f.iter() {
+      for k in 0..t.len() {
+        let (a, b) = (m[t[k] as usize], m[t[(k + 1) % t.len()] as usize]);
+        if a == b { continue; } // degenerated
+        let e = es.entry((a.min(b), a.max(b))).or_insert((fi, 0, false));
+        e.1 += 1;
+        if e.0 != fi { e.2 = true; }
+      }
+    }
+  }
+  let mut adj = vec![Vec::<u32>::new(); vtx.len()];
+  for (&(a, b), &(_fi, n, s)) in es.iter() {
+    if !inner && n >= 2 && !s { continue; } // inside the face
+    adj[a as usize].push(b);
+    adj[b as usize].push(a);
+  }
+  adj.into_iter().enumerate().filter_map(|(i, mut v)| {
+    if v.is_empty() { return None; }
+    v.sort();
+    Some((i as u32, v))
+  }).collect()
 }
 
 /// calc cg with volume
 /// - idx: index of triangles on each faces
 /// - vtx: length &ge; 3
 /// - p: precision for equality
+///
 /// when use += need trait Float + std::ops::AddAssign &lt; [F; 3] &gt;
-pub fn calc_cg_with_volume<F: Float + std::fmt::Debug>(
-  idx: &Vec<Vec<[u16; 3]>>, vtx: &Vec<[F; 3]>, p: F) -> (Vec<F>, F)
-  where F: std::iter::Sum {
+pub fn calc_cg_with_volume<F: Float + std::fmt::Debug + std::iter::Sum>(
+  idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], p: F) -> (Vec<F>, F) {
   let o = <F>::from(0).unwrap();
   let mut m_total = o; // 6 * volume
   let mut moi = [o, o, o];
   for f in idx.iter() {
     for t in f.iter() {
-      let vs = (0..t.len()).into_iter().map(|i|
+      let vs = (0..t.len()).map(|i|
         vtx[t[i] as usize]).collect::<Vec<_>>();
       let c = calc_cg_o(&vs);
       // let m = vs[2].dot(&vs[0].cross(&vs[1])); // iter::Sum
@@ -173,25 +376,24 @@ pub fn calc_cg_with_volume<F: Float + std::fmt::Debug>(
 /// - idx: index of triangles on each faces
 /// - vtx: length &ge; 3
 /// - p: precision for equality
+///
 /// when use += need trait Float + std::ops::AddAssign &lt; [F; 3] &gt;
-pub fn calc_cg<F: Float + std::fmt::Debug>(
-  idx: &Vec<Vec<[u16; 3]>>, vtx: &Vec<[F; 3]>, p: F) -> Vec<F>
-  where F: std::iter::Sum {
+pub fn calc_cg<F: Float + std::fmt::Debug + std::iter::Sum>(
+  idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], p: F) -> Vec<F> {
   let (cg, _vol) = calc_cg_with_volume(idx, vtx, p);
   cg
 }
 
 /// calc cg skip o
 /// - vs: length = 3 (It means: (o + vs[0] + vs[1] + vs[2]) / 4)
-pub fn calc_cg_o<F: Float>(vs: &Vec<[F; 3]>) -> Vec<F> {
+pub fn calc_cg_o<F: Float>(vs: &[[F; 3]]) -> Vec<F> {
   let n = <F>::from(4).unwrap(); // always 4
   sum_f3(vs).iter().map(|&v| v / n).collect()
 }
 
 /// adjust cg with volume
-pub fn adjust_cg_with_volume<F: Float + std::fmt::Debug>(
-  idx: &Vec<Vec<[u16; 3]>>, vtx: &mut Vec<[F; 3]>, p: F) -> (Vec<F>, F)
-  where F: std::iter::Sum {
+pub fn adjust_cg_with_volume<F: Float + std::fmt::Debug + std::iter::Sum>(
+  idx: &[Vec<[u32; 3]>], vtx: &mut [[F; 3]], p: F) -> (Vec<F>, F) {
   let (cg, vol) = calc_cg_with_volume(idx, vtx, p);
   // println!("cg: {:?}", cg);
   translate(vtx, &[-cg[0], -cg[1], -cg[2]]);
@@ -207,20 +409,74 @@ pub fn adjust_cg_with_volume<F: Float + std::fmt::Debug>(
 }
 
 /// adjust cg
-pub fn adjust_cg<F: Float + std::fmt::Debug>(
-  idx: &Vec<Vec<[u16; 3]>>, vtx: &mut Vec<[F; 3]>, p: F) -> Vec<F>
-  where F: std::iter::Sum {
+pub fn adjust_cg<F: Float + std::fmt::Debug + std::iter::Sum>(
+  idx: &[Vec<[u32; 3]>], vtx: &mut [[F; 3]], p: F) -> Vec<F> {
   let (cg, _vol) = adjust_cg_with_volume(idx, vtx, p);
   cg
 }
 
+/// normal of the triangle (normalized, zero when degenerated)
+pub fn tri_normal<F: Float + std::fmt::Debug + std::iter::Sum>(
+  vs: &[[F; 3]; 3]) -> [F; 3] {
+  let a = [vs[1][0] - vs[0][0], vs[1][1] - vs[0][1], vs[1][2] - vs[0][2]];
+  let b = [vs[2][0] - vs[0][0], vs[2][1] - vs[0][1], vs[2][2] - vs[0][2]];
+  let n = a.cross(&b);
+  let d = n.dot(&n).sqrt();
+  if d == <F>::from(0).unwrap() { return n; }
+  [n[0] / d, n[1] / d, n[2] / d]
+}
+
 /// translate
-pub fn translate<F: Float + std::fmt::Debug>(vtx: &mut Vec<[F; 3]>, o: &[F]) {
+pub fn translate<F: Float + std::fmt::Debug>(vtx: &mut [[F; 3]], o: &[F]) {
   for v in vtx.iter_mut() {
     *v = [v[0] + o[0], v[1] + o[1], v[2] + o[2]];
   }
 }
 
+/// scale
+pub fn scale<F: Float>(vtx: &mut [[F; 3]], s: &[F; 3]) {
+  for v in vtx.iter_mut() {
+    *v = [v[0] * s[0], v[1] * s[1], v[2] * s[2]];
+  }
+}
+
+/// mirror on the plane n . x = d
+/// - n: unit normal
+pub fn mirror<F: Float>(vtx: &mut [[F; 3]], n: &[F; 3], d: F) {
+  let z = <F>::from(2).unwrap();
+  for v in vtx.iter_mut() {
+    let s = z * (n[0] * v[0] + n[1] * v[1] + n[2] * v[2] - d);
+    *v = [v[0] - s * n[0], v[1] - s * n[1], v[2] - s * n[2]];
+  }
+}
+
+/// transform by Matrix4 (Vector4 [x, y, z, 1] dot_mv m, w is ignored)
+/// returns det of the 3x3 part (volume ratio, negative when mirrored)
+pub fn transform<F: Float + std::fmt::Debug + std::iter::Sum>(
+  vtx: &mut [[F; 3]], m: &Matrix4<F>) -> F {
+  let l = <F>::from(1).unwrap();
+  for v in vtx.iter_mut() {
+    let p = Vector4::<F>::new(&vec![v[0], v[1], v[2], l]).dot_mv(m);
+    *v = [p[0], p[1], p[2]];
+  }
+  Matrix3::rowmajor3((0..3).map(|j| [m[j][0], m[j][1], m[j][2]]).collect())
+    .det()
+}
+
+/// scale vol by |det| and flip winding when det is negative
+/// (after the vertices are transformed)
+fn apply_det<F: Float, T: TUV<F> + ?Sized>(ph: &mut T, det: F) {
+  if let Some(v) = ph.ref_vol_mut() { *v = *v * det.abs(); }
+  if det < <F>::from(0).unwrap() { ph.flip_winding(); }
+}
+
+/// flip winding of the triangles (uv together, uv may be empty)
+pub fn flip_winding<F: Float>(idx: &mut [Vec<[u32; 3]>],
+  uv: &mut [Vec<[[F; 2]; 3]>]) {
+  for t in idx.iter_mut().flatten() { t.swap(1, 2); }
+  for t in uv.iter_mut().flatten() { t.swap(1, 2); }
+}
+
 /// round precision
 pub fn round_prec<F: Float>(v: &[F], e: F, q: F) -> Vec<F> {
   let o = <F>::from(0).unwrap();
@@ -242,12 +498,14 @@ pub fn f_to_f64<F: Float>(v: &[F]) -> Vec<f64> {
 /// tests
 #[cfg(test)]
 mod tests {
-  use super::{prec_eq, f_to_f32};
+  use super::{prec_eq, prec_eq_f, f_to_f32};
   use super::polyhedron::TUV;
   // use super::polyhedron::tetra::*;
   use super::tetra::*; // short cut
   use super::cube::*;
   use super::octa::*;
+  use super::icosa::*;
+  use super::dodeca::*;
   use super::pipe::*;
   use super::polyhedron; // polyhedron::pin::Pin
 
@@ -342,18 +600,261 @@ mod tests {
     assert_eq!(s32, "[1.0, 0.0, 0.0]");
   }
 
+  #[test]
+  fn test_icosa() {
+    let icosa64_e = Icosa::new(1.0f64);
+    let icosa64 = icosa64_e.ph;
+    assert_eq!(icosa64.tri.len(), 20);
+    assert_eq!(icosa64.vtx.len(), 12);
+    assert!(icosa64.vtx.iter().all(|v|
+      prec_eq_f(v.iter().map(|&p| p * p).sum::<f64>(), 1e-12, 1.0)));
+    assert!(prec_eq_f(icosa64.vol, 1e-12, 2.5361507101204097));
+    let (cg, _vol) = icosa64.calc_cg_with_volume(1e-6);
+    assert_eq!(f_to_f32(&cg), &[0.0, 0.0, 0.0]);
+  }
+
+  #[test]
+  fn test_dodeca() {
+    let dodeca64_e = Dodeca::new(1.0f64);
+    let dodeca64 = dodeca64_e.ph;
+    assert_eq!(dodeca64.tri.len(), 12);
+    assert_eq!(dodeca64.tri[0].len(), 3);
+    assert_eq!(dodeca64.vtx.len(), 20);
+    assert!(prec_eq_f(dodeca64.vol, 1e-12, 2.785163863122623));
+
+    let uv = format!("{:?}", dodeca64.with_uv(false)[0][0][0].uv);
+    println!("{}", uv);
+    assert_eq!(uv, "[0.5, 0.0]");
+
+    let dodeca32_e = DodecaCenter::new(1.0f32);
+    let dodeca32 = dodeca32_e.ph;
+    assert_eq!(dodeca32.tri.len(), 12);
+    assert_eq!(dodeca32.tri[0].len(), 5);
+    assert_eq!(dodeca32.vtx.len(), 20 + 12);
+    assert!(prec_eq_f(dodeca32.vol, 1e-5, 2.7851639));
+
+    let uv32 = format!("{:?}", dodeca32.with_uv(false)[0][0][0].puv());
+    println!("{}", uv32);
+    assert_eq!(uv32, "([0.0, 0.6759735, 0.4177746], [0.5, 0.5])");
+  }
+
+  #[test]
+  fn test_edges() {
+    let tetra = Tetra::new(1.0f32);
+    assert_eq!(tetra.edges.len(), 4);
+    assert_eq!(tetra.edges[0], (0, vec![1, 2, 3]));
+
+    let cube = Cube::new(1.0f32);
+    assert_eq!(cube.edges.len(), 8); // merged 24 to 8
+    assert!(cube.edges.iter().all(|(_, v)| v.len() == 3));
+    let n = cube.ph.calc_edges(Some(1e-6), true).iter().map(|(_, v)|
+      v.len()).sum::<usize>();
+    assert_eq!(n, (12 + 6) * 2); // with diagonals
+    assert_eq!(cube.ph.calc_edges(None, false).len(), 24);
+
+    let icosa = Icosa::new(1.0f32);
+    assert!(icosa.edges.iter().all(|(_, v)| v.len() == 5));
+    let dodeca = DodecaCenter::new(1.0f32);
+    assert_eq!(dodeca.edges.len(), 20); // without center
+    assert!(dodeca.edges.iter().all(|(_, v)| v.len() == 3));
+
+    let torus = polyhedron::torus::Torus::new(2.0f32, 0.5, 2, 2);
+    assert_eq!(torus.edges.len(), 8 * 8);
+    assert!(torus.edges.iter().all(|(_, v)| v.len() == 4));
+  }
+
+  #[test]
+  fn test_obj() {
+    let cube = Cube::new(1.0f32);
+    let mut buf = Vec::<u8>::new();
+    cube.ph.write_obj(&mut buf, true, Some("cube")).unwrap();
+    let s = String::from_utf8(buf).unwrap();
+    let count = |k: &str| s.lines().filter(|l|
+      l.split(' ').next() == Some(k)).count();
+    assert_eq!(count("v"), 24);
+    assert_eq!(count("vt"), 6 * 2 * 3);
+    assert_eq!(count("vn"), 6 * 2);
+    assert_eq!(count("f"), 6 * 2);
+    assert_eq!(count("g"), 6);
+    assert_eq!(count("usemtl"), 6);
+    assert!(s.contains("mtllib cube.mtl"));
+    assert!(s.contains("usemtl cube\n"));
+    assert!(s.contains("vn 1 0 0"));
+    assert!(s.contains("f 1/1/1 2/2/1 3/3/1"));
+
+    let mut buf = Vec::<u8>::new();
+    cube.ph.write_mtl(&mut buf, false, "cube").unwrap();
+    let s = String::from_utf8(buf).unwrap();
+    assert_eq!(s.lines().filter(|l| l.starts_with("newmtl")).count(), 6);
+    assert!(s.contains("map_Kd cube_5.png"));
+  }
+
+  #[test]
+  fn test_stl() {
+    let cube = Cube::new(1.0f64);
+    for binary in [true, false] {
+      let mut buf = Vec::<u8>::new();
+      cube.ph.write_stl(&mut buf, binary).unwrap();
+      if binary { assert_eq!(buf.len(), 84 + 50 * 12); }
+      let ph = super::stl::read_stl(&mut buf.as_slice(), 1e-6).unwrap();
+      assert_eq!(ph.vtx.len(), 8); // welded
+      assert_eq!(ph.tri.len(), 12);
+      assert!(prec_eq_f(ph.vol, 1e-6, 8.0));
+    }
+
+    let pin = polyhedron::pin::Pin::new(1.0f64, 8, 6);
+    let mut buf = Vec::<u8>::new();
+    pin.ph.write_stl(&mut buf, true).unwrap();
+    let ph = super::stl::read_stl(&mut buf.as_slice(), 1e-5).unwrap();
+    assert_eq!(ph.vtx.len(), (8*2+1) * (6*4) + 2); // welded seam and caps
+    assert!(prec_eq_f(ph.vol, 1e-3, pin.ph.vol));
+  }
+
+  #[test]
+  fn test_normal() {
+    use super::normal::NormalMode;
+    use super::sphere::RSphere;
+    use super::cylinder::Cylinder;
+    let p = 1e-6;
+    let cube = Cube::new(1.0f64);
+    let ns = cube.ph.calc_normals(NormalMode::Face, p);
+    assert_eq!(ns[0][1][2], [1.0, 0.0, 0.0]);
+    let pi = std::f64::consts::PI;
+    let ns = cube.ph.calc_normals(NormalMode::Angle(pi / 3.0), p);
+    assert_eq!(ns[5][0][0], [0.0, 0.0, -1.0]); // split on the crease
+    let ns = cube.ph.calc_normals(NormalMode::Angle(pi), p);
+    let k = 1.0 / 3.0f64.sqrt();
+    assert!(prec_eq(&ns[0][0][0], 1e-12, &[k, -k, k]));
+
+    let phf = cube.ph.with_normal(true, NormalMode::Triangle, p);
+    let (v, n) = phf[2][1][2].pn();
+    assert_eq!((v, n), (&[1.0, 1.0, 1.0], Some(&[0.0, 1.0, 0.0])));
+
+    let sphere = RSphere::new(1.0f64, 8);
+    let ns = sphere.ph.calc_normals(NormalMode::Area(1.0), p);
+    for (f, nf) in sphere.ph.tri.iter().zip(ns.iter()) {
+      for (t, nt) in f.iter().zip(nf.iter()) {
+        for (&i, n) in t.iter().zip(nt.iter()) {
+          let v = sphere.ph.vtx[i as usize];
+          assert!(n[0] * v[0] + n[1] * v[1] + n[2] * v[2] > 0.999);
+        }
+      }
+    }
+
+    let cylinder = Cylinder::new(1.0f64, 2.0, 4);
+    let ns = cylinder.ph.calc_normals(NormalMode::Angle(1.0), p);
+    assert_eq!(ns[0][0][1], [0.0, -1.0, 0.0]); // bottom
+    assert!(prec_eq(&ns[1][0][0], 1e-12, &[0.0, 0.0, 1.0])); // side
+  }
+
+  #[test]
+  fn test_mass() {
+    use qm::q::{TQuaternion, Quaternion};
+    use qm::v::{TVector, v4::Vector4};
+    use super::sphere::RSphere;
+    use super::cylinder::Cylinder;
+    let cube = Cube::new(1.0f64); // edge 2
+    let mp = cube.ph.mass_properties(1.0);
+    assert!(prec_eq_f(mp.mass, 1e-12, 8.0));
+    for j in 0..3 {
+      for k in 0..3 {
+        let e = if j == k { 8.0 * (4.0 + 4.0) / 12.0 } else { 0.0 };
+        assert!(prec_eq_f(mp.inertia[j][k], 1e-12, e));
+      }
+    }
+
+    let cuboid = Cuboid::new([1.0f64, 2.0, 3.0]);
+    let mp = cuboid.ph.mass_properties_with_mass(6.0);
+    assert!(prec_eq(&mp.principal, 1e-12, &[2.5, 5.0, 6.5]));
+    let rot = mp.axes.to_m4_rot();
+    let ax = |v: [f64; 3]| {
+      let d = Vector4::<f64>::new(&vec![v[0], v[1], v[2], 1.0]).dot_mv(&rot);
+      [d[0].abs(), d[1].abs(), d[2].abs()]
+    };
+    assert!(prec_eq(&ax([1.0, 0.0, 0.0]), 1e-12, &[0.0, 0.0, 1.0])); // min
+    assert!(prec_eq(&ax([0.0, 0.0, 1.0]), 1e-12, &[1.0, 0.0, 0.0])); // max
+    let q = Quaternion::<f64>::from_axis_and_angle(&[1.0, 2.0, 3.0], 0.7);
+    let rot = q.to_m4_rot();
+    let r = [0, 1, 2].map(|i| {
+      let mut e = vec![0.0, 0.0, 0.0, 1.0];
+      e[i] = 1.0;
+      Vector4::<f64>::new(&e).dot_mv(&rot)
+    }); // rows: rotated X Y Z axes
+    let r = [0, 1, 2].map(|k| [r[0][k], r[1][k], r[2][k]]);
+    let p = super::mass::quaternion_from_columns(&r);
+    assert!(prec_eq(&p, 1e-12, &q));
+
+    let (r, l) = (1.0f64, 2.0f64);
+    let cylinder = Cylinder::new(r, l, 64);
+    let mp = cylinder.ph.mass_properties_with_mass(1.0);
+    assert!(prec_eq(&mp.cg, 1e-12, &[0.0, 0.0, 0.0]));
+    let (ia, ir) = (r * r / 2.0, (3.0 * r * r + l * l) / 12.0); // m = 1
+    assert!(prec_eq(&[mp.inertia[0][0], mp.inertia[1][1], mp.inertia[2][2]],
+      1e-3, &[ir, ia, ir]));
+
+    let sphere = RSphere::new(r, 16);
+    let mp = sphere.ph.mass_properties_with_mass(1.0);
+    assert!(prec_eq(&mp.principal, 1e-2, &[0.4, 0.4, 0.4]));
+  }
+
+  #[test]
+  fn test_index() {
+    use super::torus::Torus;
+    use super::error::PHError;
+    let torus = Torus::new(2.0f32, 0.5, 63, 64);
+    assert_eq!(torus.ph.vtx.len(), (63 * 4 + 1) * (64 * 4 + 1)); // 65021
+    assert_eq!(torus.ph.tri.iter().flat_map(|f| f.iter().flat_map(|t|
+      t.iter())).max(), Some(&65020));
+    assert!(torus.ph.tri_u16().is_ok());
+    let torus = Torus::new(2.0f32, 0.5, 64, 64);
+    assert_eq!(torus.ph.tri_u16().err(),
+      Some(PHError::IndexOverflow(257 * 257, 65536)));
+    assert!(Torus::try_new(2.0f32, 0.5, 65535, 65535).is_err());
+  }
+
+  #[test]
+  fn test_try_new() {
+    use super::sphere::RSphere;
+    use super::revolution::Revolution;
+    use super::pin::Pin;
+    use super::torus::{Torus, RTorus};
+    use super::error::PHError;
+    let e = RSphere::try_new(1.0f32, 0).err().unwrap();
+    assert_eq!(e, PHError::InvalidParameter("q must be > 0".to_string()));
+    assert_eq!(format!("{}", e), "invalid parameter: q must be > 0");
+    assert!(matches!(Cube::try_new(-1.0f32),
+      Err(PHError::InvalidParameter(_))));
+    assert!(matches!(Cube::try_new(f32::NAN),
+      Err(PHError::InvalidParameter(_))));
+    assert!(matches!(Pin::try_new(1.0f32, 7, 6),
+      Err(PHError::InvalidParameter(_))));
+    assert!(matches!(HalfPipe::try_new(1.0f32, 0.4, 0.5, 1.0, 6),
+      Err(PHError::InvalidParameter(_))));
+    let revo = Revolution::try_new(1.0f32, 2, 2, (true, true),
+      |_n: u32, _m: u32| (0.0, 0.0));
+    assert!(matches!(revo, Err(PHError::Degenerate(_))));
+    assert!(Icosa::try_new(2.0f64).is_ok());
+    assert!(Tetra::try_new(1e4f32).is_ok()); // large r in f32
+    assert!(Pin::try_new(100.0f32, 8, 6).is_ok());
+    assert!(HalfPipe::try_new(4.712389f32, 500.0, 400.0, 1000.0, 6).is_ok());
+    assert!(matches!(Torus::try_new(1.0f32, 2.0, 2, 2),
+      Err(PHError::InvalidParameter(_))));
+    assert!(matches!(RTorus::try_new(1.0f32, 1.0, 2, 2),
+      Err(PHError::InvalidParameter(_))));
+  }
+
   #[test]
   fn test_tube() {
     let tube32_e = Tube::new(0.5, 0.4, 1.0, 6);
     let tube32 = tube32_e.ph;
     assert_eq!(tube32.tri.len(), 4 * (6*4));
-    assert_eq!(tube32.vtx.len(), (2*2+1) * (6*4));
+    assert_eq!(tube32.vtx.len(), (2*2+1) * (6*4+1)); // duplex seam
   }
 
   #[test]
   fn test_halfpipe() {
 //    let cmp = [0.0, 0.5, 0.2 - 0.05825041967286819]; // [0, l/2, idm/2 - cg]
-    let cmp = [0.0, 0.5, 0.2 - 0.06758362864954912]; // [0, l/2, idm/2 - cg]
+    let cmp = [0.0, 0.5, 0.2 - 0.067_583_63]; // [0, l/2, idm/2 - cg]
     let halfpipe32_e = HalfPipe::new(4.712388980, 0.5, 0.4, 1.0, 6); // 3pi/2
 /*
     let cmp = [0.0, 0.5, 0.2]; // [0, l/2, idm/2]
@@ -372,10 +873,857 @@ mod tests {
     let pin32_e = polyhedron::pin::Pin::new(1.0f32, 8, 6);
     let pin32 = pin32_e.ph;
     assert_eq!(pin32.tri.len(), 432);
-    assert_eq!(pin32.vtx.len(), 410); // (8*2+1) * (6*4) + 2 (bottom, top)
-    let btm32 = pin32.vtx[(8*2+1) * (6*4)]; // bottom
+    assert_eq!(pin32.vtx.len(), 475); // (8*2+1) * (6*4+1) + 2 + 2 * (6*4)
+    let btm32 = pin32.vtx[(8*2+1) * (6*4+1)]; // bottom
     println!("{:?}", btm32);
 //    assert!(prec_eq(&f_to_f32(&btm32), 1e-6, &[0.0, -5.8672757, 0.0]));
     assert!(prec_eq(&f_to_f32(&btm32), 1e-6, &[0.0, -5.779917, 0.0]));
   }
+
+  #[test]
+  fn test_uv() {
+    use super::sphere::RSphere;
+    use super::cylinder::Cylinder;
+    use super::capsule::Capsule;
+    use super::cone::Cone;
+    use super::torus::{Torus, RTorus, Ring};
+    use super::pin::Pin;
+    let phs = [
+      RSphere::new(1.0f64, 4).ph,
+      Cylinder::new(1.0, 2.0, 4).ph,
+      Capsule::new(1.0, 2.0, 4).ph,
+      Cone::new(1.0, 2.0, 4).ph,
+      Torus::new(2.0, 0.5, 4, 3).ph,
+      RTorus::new(2.0, 0.5, 4, 3).ph,
+      Ring::new(2.0, 0.5, 0.3, 4, 3).ph,
+      Pin::new(1.0, 8, 6).ph];
+    for ph in phs.iter() {
+      let (tri, uv) = (ph.ref_tri(), ph.ref_uv());
+      assert_eq!(uv.len(), tri.len());
+      let mut vuv = vec![None; ph.ref_vtx().len()];
+      for (f, fuv) in tri.iter().zip(uv.iter()) {
+        assert_eq!(fuv.len(), f.len());
+        for (t, tuv) in f.iter().zip(fuv.iter()) {
+          for (&i, p) in t.iter().zip(tuv.iter()) {
+            assert!(p.iter().all(|&q| (0.0..=1.0).contains(&q)));
+            let v = vuv[i as usize].get_or_insert(*p);
+            assert_eq!(v, p); // one uv on each vertex (duplex seam)
+          }
+          let us = tuv.map(|p| p[0]);
+          let w = us.iter().fold(0.0f64, |m, &a| m.max(a))
+            - us.iter().fold(1.0f64, |m, &a| m.min(a));
+          assert!(w < 0.5); // not across the seam
+        }
+      }
+      assert_eq!(ph.with_uv(true).len(), tri.len());
+      assert!(ph.vol > 0.0); // not flipped
+    }
+
+    let sphere = RSphere::new(1.0f32, 4);
+    let f = &sphere.ph.uv[4 * 16 + 15]; // sn = 4 (equator) cn = c - 1
+    assert!(prec_eq(&f[0][1], 1e-6, &[1.0, 0.5])); // duplex seam u = 1
+  }
+
+  #[test]
+  fn test_uvt() {
+    use super::polyhedron::UVT;
+    let cube = Cube::new(1.0f32);
+    let uvs = |phf: &polyhedron::PHF<f32>| phf.iter().flat_map(|f|
+      f.iter().flat_map(|t| t.iter().map(|v| v.uv))).collect::<Vec<_>>();
+    let base = uvs(&cube.ph.with_uv(true));
+    assert_eq!(uvs(&cube.ph.with_uv_t(true, &UVT::default())), base);
+
+    let atlas = UVT::new(0.0, [0.5, 0.25], [0.5, 0.0]); // right upper cell
+    let placed = uvs(&cube.ph.with_uv_t(true, &atlas));
+    for (p, q) in base.iter().zip(placed.iter()) {
+      assert!(prec_eq(q, 1e-6, &[0.5 + p[0] * 0.5, p[1] * 0.25]));
+    }
+
+    let rot = UVT::new(std::f64::consts::PI, [1.0, 1.0], [0.0, 0.0])
+      .pivot([0.5, 0.5]);
+    assert!(prec_eq(&rot.apply(&[0.0f32, 0.25]), 1e-6, &[1.0, 0.75]));
+    let rot = UVT::new(std::f64::consts::PI / 2.0, [2.0, 1.0], [0.1, 0.0]);
+    assert!(prec_eq(&rot.apply(&[0.5f64, 0.0]), 1e-6, &[0.1, 1.0]));
+    assert_eq!(cube.ph.with_uv_t(false, &atlas)[0][0][0].uv,
+      cube.ph.with_uv(false)[0][0][0].uv); // each face is not transformed
+    let rot = UVT::new(std::f64::consts::PI / 2.0, [2.0, 2.0], [0.1, 0.0]);
+    assert!(prec_eq(&cube.ph.get_uv_t(0, 0, 1, rot.r, 2.0, rot.o), 1e-6,
+      &rot.apply(&base[1])));
+  }
+
+  #[test]
+  fn test_icosphere() {
+    use super::icosphere::{IcoSphere, Class};
+    let icosa = Icosa::new(1.0f64);
+    let ico1 = IcoSphere::new(1.0f64, 1, Class::I);
+    assert_eq!(ico1.ph.vtx.len(), 12);
+    assert!(prec_eq_f(ico1.ph.vol, 1e-9, icosa.ph.vol));
+
+    let vol = 4.0 * std::f64::consts::PI / 3.0;
+    for (class, f, nv, nt) in [
+      (Class::I, 4, 162, 320), (Class::II, 2, 122, 240)] {
+      let ico = IcoSphere::new(1.0f64, f, class);
+      assert_eq!(ico.ph.vtx.len(), nv); // deduplicated
+      assert_eq!(ico.ph.tri.len(), nt);
+      assert!(ico.ph.vtx.iter().all(|v|
+        prec_eq_f(v.iter().map(|p| p * p).sum::<f64>(), 1e-9, 1.0)));
+      assert!(ico.ph.vol > icosa.ph.vol && ico.ph.vol < vol);
+      assert!(ico.edges.iter().all(|(_, v)| v.len() == 5 || v.len() == 6));
+      let ls = ico.edges.iter().flat_map(|(a, v)| v.iter().map(|&b| {
+        let (p, q) = (ico.ph.vtx[*a as usize], ico.ph.vtx[b as usize]);
+        (0..3).map(|k| (p[k] - q[k]).powi(2)).sum::<f64>().sqrt()
+      })).collect::<Vec<_>>();
+      let (mn, mx) = ls.iter().fold((f64::MAX, 0.0f64), |(mn, mx), &l|
+        (mn.min(l), mx.max(l)));
+      assert!(mx / mn < 1.35); // uniform
+    }
+    assert!(IcoSphere::try_new(1.0f32, 0, Class::I).is_err());
+  }
+
+  #[test]
+  fn test_conway() {
+    use super::conway::Conway;
+    let closed = |c: &Conway<f64>| {
+      let mut es = std::collections::HashMap::new();
+      for f in c.faces.iter() {
+        for i in 0..f.len() {
+          *es.entry((f[i], f[(i + 1) % f.len()])).or_insert(0) += 1;
+        }
+      }
+      es.iter().all(|(&(a, b), &n)| n == 1 && es.get(&(b, a)) == Some(&1))
+    };
+    for (s, v, f) in [("C", 8, 6), ("dC", 6, 8), ("kC", 14, 24),
+      ("aC", 12, 14), ("tC", 24, 14), ("jC", 14, 12), ("eC", 24, 26),
+      ("gC", 38, 24), ("sC", 24, 38), ("cC", 32, 18), ("dkT", 12, 8)] {
+      let c = Conway::<f64>::parse(s).unwrap();
+      assert_eq!((c.vtx.len(), c.faces.len()), (v, f), "{}", s);
+      assert!(closed(&c), "{}", s);
+      assert!(c.to_polyhedron().vol > 0.0, "{}", s);
+    }
+
+    let c60 = Conway::<f64>::parse("tI").unwrap();
+    assert_eq!(c60.faces.iter().filter(|f| f.len() == 5).count(), 12);
+    assert_eq!(c60.faces.iter().filter(|f| f.len() == 6).count(), 20);
+    let ls = c60.faces.iter().flat_map(|f| (0..f.len()).map(|i| {
+      let (a, b) = (c60.vtx[f[i] as usize],
+        c60.vtx[f[(i + 1) % f.len()] as usize]);
+      (0..3).map(|k| (a[k] - b[k]).powi(2)).sum::<f64>().sqrt()
+    })).collect::<Vec<_>>();
+    assert!(ls.iter().all(|&l| prec_eq_f(l, 1e-9, ls[0]))); // uniform edges
+    let dd = Conway::<f64>::parse("ddI").unwrap();
+    let icosa = Icosa::new(1.0f64);
+    assert!(dd.vtx.iter().all(|p| icosa.ph.vtx.iter().any(|q|
+      prec_eq(p, 1e-9, q)))); // reciprocal about the midsphere
+
+    let tc = Icosa::new(1.0f64).ph.conway("t", 1e-6).unwrap();
+    assert_eq!(tc.tri.len(), 32); // per face grouping
+    assert_eq!(tc.vtx.len(), 60);
+    assert!(Conway::<f64>::parse("xI").is_err());
+    assert!(Conway::<f64>::parse("tX").is_err());
+    assert_eq!(super::fan_indexed(&[vec![], vec![0, 1, 2, 3]]),
+      vec![vec![], vec![[0, 1, 2], [0, 2, 3]]]); // empty polygon
+  }
+
+  #[test]
+  fn test_archimedean() {
+    use super::archimedean::{Archimedean, ArchimedeanCenter, Solid};
+    let vef = [(12, 18, 8), (12, 24, 14), (24, 36, 14), (24, 36, 14),
+      (24, 48, 26), (48, 72, 26), (24, 60, 38), (30, 60, 32), (60, 90, 32),
+      (60, 90, 32), (60, 120, 62), (120, 180, 62), (60, 150, 92)];
+    for (s, (v, e, f)) in Solid::ALL.iter().zip(vef.iter()) {
+      let a = Archimedean::new(*s, 1.0f64);
+      assert_eq!(a.ph.vtx.len(), *v, "{:?}", s);
+      assert_eq!(a.ph.tri.len(), *f, "{:?}", s);
+      assert_eq!(a.edges.iter().map(|(_, w)| w.len()).sum::<usize>(), e * 2);
+      assert!(a.ph.vtx.iter().all(|p|
+        prec_eq_f(p.iter().map(|q| q * q).sum::<f64>(), 1e-9, 1.0)));
+      let l = a.edges.iter().flat_map(|(i, w)| w.iter().map(|&j| {
+        let (p, q) = (a.ph.vtx[*i as usize], a.ph.vtx[j as usize]);
+        (0..3).map(|k| (p[k] - q[k]).powi(2)).sum::<f64>().sqrt()
+      })).collect::<Vec<_>>();
+      assert!(l.iter().all(|&d| prec_eq_f(d, 1e-9, l[0])), "{:?}", s);
+      let c = ArchimedeanCenter::new(*s, 1.0f64);
+      assert_eq!(c.ph.vtx.len(), v + f);
+      assert!(prec_eq_f(c.ph.vol, 1e-9, a.ph.vol) && a.ph.vol > 0.0);
+    }
+
+    let tc = Archimedean::new(Solid::TruncatedCube, 1.0f32);
+    let oct = tc.ph.tri.iter().position(|f| f.len() == 6).unwrap(); // octagon
+    let uv = format!("{:?}", tc.ph.with_uv(false)[oct][0][2].uv);
+    assert_eq!(uv, "[0.0, 0.5]"); // vertex 2 of the regular octagon (pi)
+    let tc = ArchimedeanCenter::new(Solid::TruncatedCube, 1.0f32);
+    let uv = format!("{:?}", tc.ph.with_uv(false)[oct][3][0].uv);
+    assert_eq!(uv, "[0.5, 0.5]"); // center
+  }
+
+  #[test]
+  fn test_johnson() {
+    use super::prism::{Prism, Antiprism};
+    use super::pyramid::{Pyramid, Bipyramid};
+    use super::cupola::{Cupola, Rotunda};
+    use super::johnson::{Johnson, Solid};
+    let pi = std::f64::consts::PI;
+    let base = |n: f64| n / 2.0 * (2.0 * pi / n).sin(); // n-gon area (r = 1)
+    let p = Prism::new(1.0f64, 2.0, 7);
+    assert_eq!((p.ph.vtx.len(), p.ph.tri.len()), (14, 9));
+    assert!(prec_eq_f(p.ph.vol, 1e-9, base(7.0) * 2.0));
+    let a = Antiprism::new(1.0f64, 0.5, 9);
+    assert_eq!((a.ph.vtx.len(), a.ph.tri.len()), (18, 20));
+    assert!(a.ph.vol > 0.0 && a.ph.tri[2].len() == 1);
+    let y = Pyramid::new(1.0f64, 3.0, 6);
+    assert_eq!((y.ph.vtx.len(), y.ph.tri.len()), (7, 7));
+    assert!(prec_eq_f(y.ph.vol, 1e-9, base(6.0)));
+    let b = Bipyramid::new(1.0f64, 1.5, 8);
+    assert_eq!((b.ph.vtx.len(), b.ph.tri.len()), (10, 16));
+    assert!(prec_eq_f(b.ph.vol, 1e-9, base(8.0)));
+    let c = Cupola::new(1.0f64, (2.0f64 / 3.0).sqrt(), 3); // regular J3
+    assert_eq!((c.ph.vtx.len(), c.ph.tri.len()), (9, 8));
+    assert!(prec_eq_f(c.ph.vol, 1e-9, 5.0 * 2.0f64.sqrt() / 6.0));
+    let phi = (1.0 + 5.0f64.sqrt()) / 2.0;
+    let r = Rotunda::new(phi, 5); // J6
+    assert_eq!((r.ph.vtx.len(), r.ph.tri.len()), (20, 17));
+    assert!(prec_eq_f(r.ph.vol, 1e-9, (45.0 + 17.0 * 5.0f64.sqrt()) / 12.0));
+    assert!(Rotunda::new(1.0f64, 8).ph.vol > 0.0);
+    assert!(Prism::<f64>::try_new(1.0, 1.0, 2).is_err());
+
+    let vef = [(5, 8, 5), (6, 10, 6), (9, 15, 8), (12, 20, 10), (15, 25, 12),
+      (20, 35, 17), (7, 12, 7), (9, 16, 9), (11, 20, 11), (9, 20, 13),
+      (11, 25, 16), (5, 9, 6), (7, 15, 10), (8, 15, 9), (10, 20, 12),
+      (12, 25, 15), (10, 24, 16), (15, 27, 14), (20, 36, 18), (25, 45, 22),
+      (30, 55, 27), (15, 33, 20), (20, 44, 26), (25, 55, 32), (30, 65, 37),
+      (12, 24, 14), (16, 32, 18), (16, 32, 18), (20, 40, 22), (20, 40, 22),
+      (25, 50, 27), (25, 50, 27), (30, 60, 32), (18, 36, 20), (18, 36, 20),
+      (24, 48, 26), (30, 60, 32), (30, 60, 32), (35, 70, 37), (35, 70, 37),
+      (40, 80, 42), (40, 80, 42)];
+    for (s, (v, e, f)) in Solid::ALL.iter().zip(vef.iter()) {
+      let j = Johnson::new(*s, 1.0f64);
+      assert_eq!(j.ph.vtx.len(), *v, "{:?}", s);
+      assert_eq!(j.ph.tri.len(), *f, "{:?}", s);
+      assert_eq!(j.edges.iter().map(|(_, w)| w.len()).sum::<usize>(), e * 2);
+      let l = j.edges.iter().flat_map(|(i, w)| w.iter().map(|&k| {
+        let (p, q) = (j.ph.vtx[*i as usize], j.ph.vtx[k as usize]);
+        (0..3).map(|m| (p[m] - q[m]).powi(2)).sum::<f64>().sqrt()
+      })).collect::<Vec<_>>();
+      assert!(l.iter().all(|&d| prec_eq_f(d, 1e-9, 1.0)), "{:?}", s);
+      assert!(j.ph.vol > 0.0, "{:?}", s);
+    }
+    assert_eq!(Solid::PentagonalOrthobirotunda.number(), 34);
+    let j3 = Johnson::new(Solid::TriangularCupola, 1.0f64);
+    assert!(prec_eq_f(j3.ph.vol, 1e-9, c.ph.vol));
+    // ortho: pentagons meet pentagons (gyro would be icosidodecahedron)
+    let (_, fs) = Solid::PentagonalOrthobirotunda.polygons();
+    let pe = fs.iter().filter(|f| f.len() == 5).flat_map(|f|
+      (0..5).map(|k| (f[k], f[(k + 1) % 5]))).collect::<Vec<_>>();
+    assert!(pe.iter().any(|&(a, b)| pe.contains(&(b, a))));
+  }
+
+  #[test]
+  fn test_subdivision() {
+    let cube = Cube::new(1.0f64);
+    let cc = cube.ph.catmull_clark(1, 1e-6).unwrap();
+    assert_eq!((cc.vtx.len(), cc.tri.len()), (26, 6)); // 8 + 6 + 12
+    assert!(cc.tri.iter().all(|f| f.len() == 8)); // 4 quads on each face
+    let cc = cube.ph.catmull_clark(3, 1e-6).unwrap();
+    assert_eq!(cc.vtx.len(), 386); // 24 * 4 * 4 + 2
+    let m = |k: usize| cc.vtx.iter().map(|v| v[k].abs()).fold(0.0, f64::max);
+    assert!(prec_eq_f(m(0), 1e-9, m(1)) && prec_eq_f(m(1), 1e-9, m(2)));
+    assert!(m(0) < 1.0 && cc.vol > 2.0 && cc.vol < cube.ph.vol); // rounded
+    // uv seams are the border of the uv island (corners are kept)
+    assert_eq!(cc.uv.len(), 6);
+    assert!(cc.uv.iter().all(|f| f.len() == 8 * 16));
+    let us = cc.uv.iter().flatten().flatten().collect::<Vec<_>>();
+    assert!(us.iter().all(|u|
+      u[0] >= 0.0 && u[0] <= 0.75 && u[1] >= 0.0 && u[1] <= 1.0));
+    for c in [[0.25, 1.0], [0.5, 1.0], [0.0, 0.25], [0.75, 0.75]] {
+      assert!(us.iter().any(|u| prec_eq(&u[..], 1e-9, &c[..])), "{:?}", c);
+    }
+
+    let icosa = Icosa::new(1.0f64);
+    let ls = icosa.ph.loop_subdivide(1, 1e-6).unwrap();
+    assert_eq!((ls.vtx.len(), ls.tri.len()), (42, 20)); // 12 + 30
+    assert!(ls.tri.iter().all(|f| f.len() == 4));
+    let d = |v: &[f64; 3]| v.iter().map(|p| p * p).sum::<f64>().sqrt();
+    let r = d(&icosa.ph.vtx[0]);
+    assert!(ls.vtx.iter().all(|v| d(v) > 0.75 * r && d(v) < r));
+    assert!(ls.vol > 0.0 && ls.vol < icosa.ph.vol && ls.uv.is_empty());
+    let ls = Tetra::new(1.0f64).ph.loop_subdivide(2, 1e-6).unwrap();
+    assert_eq!(ls.vtx.len(), 34);
+  }
+
+  #[test]
+  fn test_weld() {
+    use super::weld::Weld;
+    use super::capsule::Capsule;
+    use super::torus::Torus;
+    let ren = |w: &Weld<f64>| w.calc_edges(false).into_iter().map(|(i, e)| (
+      w.split[i as usize][0],
+      e.into_iter().map(|j| w.split[j as usize][0]).collect::<Vec<_>>()))
+      .collect::<Vec<_>>(); // ids of the first render vertex
+    let cube = Cube::new(1.0f64);
+    let w = cube.ph.weld(1e-6);
+    assert_eq!((w.vtx.len(), w.map.len()), (8, 24));
+    assert!(w.split.iter().all(|s| s.len() == 3));
+    assert!(w.map.iter().enumerate().all(|(i, &k)|
+      w.split[k as usize].contains(&(i as u32))));
+    assert_eq!(ren(&w), cube.edges);
+    assert_eq!(w.degenerated(), 0);
+    assert!(prec_eq_f(w.to_polyhedron().vol, 1e-9, cube.ph.vol));
+    let capsule = Capsule::new(1.0f64, 2.0, 6);
+    let w = capsule.ph.weld(1e-6);
+    assert!(w.vtx.len() < capsule.ph.vtx.len());
+    assert_eq!(ren(&w), capsule.edges);
+    // same as the linear search
+    let w = Weld::new(&cube.ph.vtx, &cube.ph.tri, 1.0); // 2 separate by 2
+    assert_eq!(w.vtx.len(), 8);
+    let w = Weld::new(&cube.ph.vtx, &cube.ph.tri, 2.5); // all
+    assert_eq!(w.vtx.len(), 1);
+    assert_eq!(w.degenerated(), 12);
+    let torus = Torus::new(1.0f64, 0.25, 4, 4); // 289 vertices
+    let w = torus.ph.weld(1e-6);
+    assert_eq!((w.vtx.len(), w.p), (16 * 16, 1e-6));
+    assert!(w.calc_edges(false).iter().all(|(_, e)| e.len() == 4)); // quads
+  }
+
+  #[test]
+  fn test_validate() {
+    use super::validate::validate;
+    use super::archimedean::{Archimedean, ArchimedeanCenter, Solid};
+    use super::johnson::{Johnson, Solid as JSolid};
+    use super::prism::{Prism, Antiprism};
+    use super::pyramid::{Pyramid, Bipyramid};
+    use super::cupola::{Cupola, Rotunda};
+    use super::icosphere::{IcoSphere, Class};
+    use super::sphere::RSphere;
+    use super::cylinder::Cylinder;
+    use super::capsule::Capsule;
+    use super::cone::Cone;
+    use super::torus::{Torus, RTorus, Ring};
+    use super::pin::Pin;
+    use super::revolution::Revolution;
+    let mut phs = vec![
+      (Tetra::new(1.0f64).ph, 0), (Cube::new(1.0).ph, 0),
+      (CubeCenter::new(1.0).ph, 0), (Cuboid::new([1.0, 2.0, 3.0]).ph, 0),
+      (Octa::new(1.0).ph, 0), (Icosa::new(1.0).ph, 0),
+      (Dodeca::new(1.0).ph, 0), (DodecaCenter::new(1.0).ph, 0),
+      (Prism::new(1.0, 2.0, 7).ph, 0), (Antiprism::new(1.0, 0.5, 9).ph, 0),
+      (Pyramid::new(1.0, 3.0, 6).ph, 0), (Bipyramid::new(1.0, 1.5, 8).ph, 0),
+      (Cupola::new(1.0, 0.5, 4).ph, 0), (Rotunda::new(1.0, 5).ph, 0),
+      (IcoSphere::new(1.0, 3, Class::I).ph, 0),
+      (IcoSphere::new(1.0, 2, Class::II).ph, 0),
+      (RSphere::new(1.0, 4).ph, 0), (Cylinder::new(1.0, 2.0, 4).ph, 0),
+      (Capsule::new(1.0, 2.0, 4).ph, 0), (Cone::new(1.0, 2.0, 4).ph, 0),
+      (Torus::new(2.0, 0.5, 4, 3).ph, 1), (RTorus::new(2.0, 0.5, 4, 3).ph, 1),
+      (Ring::new(2.0, 0.5, 0.3, 4, 3).ph, 1), (Pin::new(1.0, 8, 6).ph, 0),
+      (Tube::new(0.5, 0.4, 1.0, 6).ph, 1),
+      (HalfPipe::new(4.712388980, 0.5, 0.4, 1.0, 6).ph, 0),
+      (Revolution::new(1.0, 2, 2, (true, true), |n, m| {
+        (n as f64 / (m - 1) as f64 - 0.5, 0.5) }).ph, 0),
+      (Icosa::new(1.0).ph.conway("t", 1e-6).unwrap(), 0),
+      (Cube::new(1.0).ph.catmull_clark(2, 1e-6).unwrap(), 0),
+      (Icosa::new(1.0).ph.loop_subdivide(2, 1e-6).unwrap(), 0)];
+    phs.extend(Solid::ALL.iter().flat_map(|&s| [
+      (Archimedean::new(s, 1.0).ph, 0),
+      (ArchimedeanCenter::new(s, 1.0).ph, 0)]));
+    phs.extend(JSolid::ALL.iter().map(|&s| (Johnson::new(s, 1.0).ph, 0)));
+    for (i, (ph, g)) in phs.iter().enumerate() {
+      let v = ph.validate(1e-6);
+      assert!(v.is_valid(), "{} {:?}", i, v);
+      assert_eq!(v.check(), Ok(()));
+      assert_eq!(v.genus(), Some(*g), "{}", i);
+      assert_eq!(v.components, 1);
+      assert!(prec_eq_f(v.vol, 1e-6, ph.vol), "{} {} {}", i, v.vol, ph.vol);
+    }
+
+    let mut icosa = Icosa::new(1.0f64).ph;
+    icosa.tri[3][0].swap(1, 2);
+    let v = icosa.validate(1e-6);
+    assert_eq!((v.inconsistent.len(), v.genus()), (3, None));
+    assert_eq!(icosa.fix_winding(1e-6), 1);
+    assert!(icosa.validate(1e-6).is_valid());
+    for f in icosa.tri.iter_mut() { for t in f.iter_mut() { t.swap(1, 2); } }
+    let v = icosa.validate(1e-6);
+    assert!(v.is_oriented() && v.vol < 0.0 && !v.is_valid());
+    assert!(v.check().is_err());
+    assert_eq!(icosa.fix_winding(1e-6), 20);
+    assert!(icosa.validate(1e-6).is_valid());
+    let (tri, vtx) = (&icosa.tri[1..].to_vec(), &icosa.vtx);
+    let v = validate(tri, vtx, 1e-6);
+    assert_eq!((v.open.len(), v.euler(), v.genus()), (3, 1, None));
+    let mut tri = icosa.tri.clone();
+    tri[0][0] = [0, 0, 1];
+    tri[1][0] = [0, 1, 99];
+    let v = validate(&tri, vtx, 1e-6);
+    assert_eq!((v.degenerate, v.out_of_range), (vec![(0, 0)], vec![(1, 0)]));
+  }
+
+  #[test]
+  fn test_query() {
+    use super::capsule::Capsule;
+    use super::pin::Pin;
+    let cube = Cube::new(1.0f64);
+    let bvh = cube.ph.bvh();
+    for b in [None, Some(&bvh)] {
+      let h = cube.ph.ray_cast(&[5.0, 0.3, 0.2], &[-1.0, 0.0, 0.0], 10.0, b)
+        .unwrap();
+      assert_eq!((h.fi, h.front), (0, true)); // +X
+      assert!(prec_eq(&h.p, 1e-12, &[1.0, 0.3, 0.2]));
+      assert!((h.t - 4.0).abs() < 1e-12);
+      let uv = h.uv.unwrap();
+      assert!(uv.iter().all(|&u| u > 0.25 && u < 0.5));
+      let t = cube.ph.tri[h.fi][h.ti].map(|i| cube.ph.vtx[i as usize]);
+      let p = [0, 1, 2].map(|j| (0..3).map(|k| t[k][j] * h.bary[k]).sum());
+      assert!(prec_eq(&p, 1e-12, &h.p));
+      let h = cube.ph.ray_cast(&[0.0; 3], &[0.0, 1.0, 0.0], 10.0, b).unwrap();
+      assert_eq!((h.fi, h.front), (2, false)); // +Y from inside
+      assert!(cube.ph.ray_cast(&[5.0, 0.3, 0.2], &[-1.0, 0.0, 0.0], 3.0, b)
+        .is_none());
+      assert!(cube.ph.ray_cast(&[5.0, 3.0, 0.2], &[-1.0, 0.0, 0.0], 10.0, b)
+        .is_none());
+      let h = cube.ph.closest_point(&[3.0, 0.2, 0.1], b).unwrap();
+      assert!(prec_eq(&h.p, 1e-12, &[1.0, 0.2, 0.1]) && h.front);
+      assert!((h.t - 2.0).abs() < 1e-12);
+      let h = cube.ph.closest_point(&[0.5, 0.0, 0.1], b).unwrap();
+      assert!((h.t - 0.5).abs() < 1e-12 && !h.front);
+      let h = cube.ph.closest_point(&[2.0, 2.0, 2.0], b).unwrap();
+      assert!(prec_eq(&h.p, 1e-12, &[1.0, 1.0, 1.0]));
+      let w = cube.ph.winding_number(&[0.2, 0.3, -0.4], b);
+      assert!((w - 1.0).abs() < 1e-9);
+      assert!(cube.ph.winding_number(&[1.2, 0.3, -0.4], b).abs() < 1e-9);
+    }
+    let caps = Capsule::new(1.0f64, 2.0, 6);
+    let pin = Pin::new(1.0f64, 8, 6);
+    let cg = pin.ph.calc_cg(1e-6);
+    for (ph, ins, outs) in [
+      (&caps.ph, vec![[0.0, 1.9, 0.0], [0.9, 0.0, 0.0], [0.0, -1.5, 0.5]],
+        vec![[0.0, 2.1, 0.0], [1.1, 0.0, 0.0], [0.8, 1.8, 0.0]]),
+      (&pin.ph, vec![[cg[0], cg[1], cg[2]]], vec![[5.0, 5.0, 5.0]])] {
+      let bvh = ph.bvh();
+      for b in [None, Some(&bvh)] {
+        assert!(ins.iter().all(|q| ph.contains(q, b)));
+        assert!(outs.iter().all(|q| !ph.contains(q, b)));
+      }
+      // Bvh gives the same results as all triangles
+      let mut s = 12345u64;
+      let mut rnd = || {
+        s = s.wrapping_mul(6364136223846793005)
+          .wrapping_add(1442695040888963407);
+        (s >> 11) as f64 / (1u64 << 53) as f64 * 8.0 - 4.0
+      };
+      for _ in 0..200 {
+        let (q, d) = ([rnd(), rnd(), rnd()], [rnd(), rnd(), rnd()]);
+        let (a, b) = (ph.ray_cast(&q, &d, 100.0, None),
+          ph.ray_cast(&q, &d, 100.0, Some(&bvh)));
+        assert_eq!(a.map(|h| (h.fi, h.ti)), b.map(|h| (h.fi, h.ti)));
+        let (a, b) = (ph.closest_point(&q, None).unwrap(),
+          ph.closest_point(&q, Some(&bvh)).unwrap());
+        assert!((a.t - b.t).abs() < 1e-12);
+        assert_eq!(ph.contains(&q, None), ph.contains(&q, Some(&bvh)));
+        let (a, b) = (ph.winding_number(&q, None),
+          ph.winding_number(&q, Some(&bvh)));
+        assert!((a - b).abs() < 0.05);
+        assert!((a - a.round()).abs() < 1e-6);
+      }
+    }
+  }
+
+  #[test]
+  fn test_hull() {
+    use super::hull::convex_hull;
+    use super::pin::Pin;
+    use super::error::PHError;
+    let cube = Cube::new(1.0f64).ph.convex_hull(1e-6).unwrap();
+    assert_eq!((cube.vtx.len(), cube.tri.len()), (8, 6));
+    assert!(cube.tri.iter().all(|f| f.len() == 2));
+    assert!((cube.vol - 8.0).abs() < 1e-12 && cube.uv.is_empty());
+    let icosa = Icosa::new(1.0f64);
+    let h = icosa.ph.convex_hull(1e-6).unwrap();
+    assert_eq!((h.vtx.len(), h.tri.len()), (12, 20));
+    assert!((h.vol - icosa.ph.vol).abs() < 1e-9);
+    let dodeca = Dodeca::new(1.0f64);
+    let h = dodeca.ph.convex_hull(1e-6).unwrap();
+    assert_eq!((h.vtx.len(), h.tri.len()), (20, 12));
+    assert!(h.tri.iter().all(|f| f.len() == 3));
+    // points inside and on the faces are dropped
+    let mut vs = Cube::new(1.0f64).ph.vtx;
+    vs.extend([[0.0, 0.0, 0.0], [1.0, 0.2, 0.3], [0.5, -1.0, 0.5]]);
+    let h = convex_hull(&vs, 1e-6).unwrap();
+    assert_eq!((h.vtx.len(), h.tri.len()), (8, 6));
+    let pin = Pin::new(1.0f64, 8, 6);
+    let h = pin.ph.convex_hull(1e-6).unwrap();
+    assert!(h.validate(1e-6).is_valid());
+    assert!(h.vol >= pin.ph.vol);
+    let bvh = h.bvh();
+    let cg = pin.ph.calc_cg(1e-6);
+    assert!(h.contains(&[cg[0], cg[1], cg[2]], Some(&bvh)));
+    for v in pin.ph.vtx.iter() { // every vertex is in or on the hull
+      let c = h.closest_point(v, Some(&bvh)).unwrap();
+      assert!(c.t < 1e-6 || h.contains(v, Some(&bvh)));
+    }
+    // point cloud
+    let mut s = 12345u64;
+    let mut rnd = || {
+      s = s.wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      (s >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    };
+    let vs = (0..2000).map(|_| [rnd(), rnd(), rnd()]).collect::<Vec<_>>();
+    let h = convex_hull(&vs, 1e-9).unwrap();
+    let v = h.validate(1e-9);
+    assert!(v.is_valid() && v.genus() == Some(0));
+    assert!(h.vol > 6.0 && h.vol < 8.0);
+    let bvh = h.bvh();
+    assert!(vs.iter().all(|q| h.closest_point(q, Some(&bvh)).unwrap().t < 1e-9
+      || h.contains(q, Some(&bvh))));
+    // degenerated
+    let flat = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0],
+      [1.0, 0.0, 1.0f64]];
+    assert!(matches!(convex_hull(&flat, 1e-6), Err(PHError::Degenerate(_))));
+    assert!(convex_hull(&flat[..3], 1e-6).is_err());
+  }
+
+  #[test]
+  fn test_slice() {
+    use super::slice::Plane;
+    use super::triangulate::{polygon_area, triangulate};
+    use super::pin::Pin;
+    let cube = Cube::new(1.0f64);
+    let y = Plane::new([0.0, 2.0, 0.0], 0.3);
+    assert!(prec_eq(&y.to_3d(&y.to_2d(&[0.5, 0.7, -0.2])), 1e-12,
+      &[0.5, 0.3, -0.2]));
+    let cs = cube.ph.slice(&y, 1e-9);
+    assert_eq!(cs.len(), 1);
+    assert!((polygon_area(&cs[0]) - 4.0).abs() < 1e-12); // counter clockwise
+    let (a, b) = cube.ph.cut(&y, 1e-9).unwrap();
+    assert!((a.vol - 2.8).abs() < 1e-12 && (b.vol - 5.2).abs() < 1e-12);
+    for h in [&a, &b] {
+      assert!(h.validate(1e-9).is_valid());
+      assert_eq!((h.tri.len(), h.uv.len()), (7, 7)); // 6 faces + cap
+      assert!(h.uv.iter().flatten().flatten().flatten()
+        .all(|&u| (0.0..=1.0).contains(&u)));
+    }
+    assert!(cube.ph.cut(&Plane::new([0.0, 1.0, 0.0], 1.5), 1e-9).is_err());
+    // on the face, on the vertices (diagonal) and oblique
+    let top = Plane::new([0.0, 1.0, 0.0], 1.0 - 2e-10);
+    assert_eq!(cube.ph.slice(&top, 1e-9).len(), 1);
+    assert!(cube.ph.cut(&top, 1e-9).is_err());
+    for pl in [Plane::new([1.0, 1.0, 0.0], 0.0),
+      Plane::new([1.0, 1.0, 1.0], 0.5)] {
+      let (a, b) = cube.ph.cut(&pl, 1e-9).unwrap();
+      assert!((a.vol + b.vol - 8.0).abs() < 1e-9);
+      assert!(a.validate(1e-9).is_valid() && b.validate(1e-9).is_valid());
+    }
+    // holes
+    let tube = Tube::new(2.0f64, 1.0, 2.0, 6);
+    let cs = tube.ph.slice(&y, 1e-9);
+    assert_eq!(cs.len(), 2);
+    let s = cs.iter().map(|c| polygon_area(c)).collect::<Vec<_>>();
+    assert!(s.iter().any(|&s| s > 0.0) && s.iter().any(|&s| s < 0.0));
+    let ring = std::f64::consts::PI * (1.0 - 0.25);
+    assert!((s[0] + s[1] - ring).abs() < 0.05);
+    let (outer, hole) = if s[0] > 0.0 { (&cs[0], &cs[1]) } else {
+      (&cs[1], &cs[0]) };
+    let ts = triangulate(outer, std::slice::from_ref(hole));
+    assert_eq!(ts.len(), outer.len() + hole.len());
+    for pl in [y, Plane::new([1.0, 0.0, 0.2], 0.1)] {
+      let (a, b) = tube.ph.cut(&pl, 1e-9).unwrap();
+      assert!((a.vol + b.vol - tube.ph.vol).abs() < 1e-9);
+      for h in [&a, &b] {
+        let v = h.validate(1e-9);
+        assert!(v.is_valid() && v.genus() == Some(if pl == y { 1 } else { 0 }));
+      }
+    }
+    // layers
+    let pin = Pin::new(1.0f64, 8, 6);
+    let (lo, hi) = pin.ph.vtx.iter().fold((f64::MAX, f64::MIN), |(lo, hi), v|
+      (lo.min(v[1]), hi.max(v[1])));
+    let ds = (0..20).map(|k| lo - 0.1 + (hi - lo + 0.2) * (k as f64 + 0.5)
+      / 20.0).collect::<Vec<_>>();
+    let layers = pin.ph.slice_many([0.0, 1.0, 0.0], &ds, 1e-9).unwrap();
+    for (d, cs) in ds.iter().zip(layers.iter()) {
+      let n = if *d < lo || *d > hi { 0 } else { 1 };
+      assert_eq!(cs.len(), n);
+      assert!(cs.iter().all(|c| polygon_area(c) > 0.0));
+      assert_eq!(cs, &pin.ph.slice(&Plane::new([0.0, 1.0, 0.0], *d), 1e-9));
+    }
+    let (a, b) = pin.ph.cut(&Plane::new([0.0, 1.0, 0.0], ds[10]), 1e-9)
+      .unwrap();
+    assert!((a.vol + b.vol - pin.ph.vol).abs() < 1e-9);
+    assert!(a.validate(1e-9).is_valid() && b.validate(1e-9).is_valid());
+    // stepped solid (faces on the plane)
+    let l = [[1.0, 1.0], [1.0, 2.0], [0.0, 2.0], [0.0, 0.0], [2.0, 0.0],
+      [2.0, 1.0f64]]; // L shape (counter clockwise from the reflex vertex)
+    let vtx = [0.0, 1.0].iter().flat_map(|&z|
+      l.iter().map(move |p| [p[0], p[1], z])).collect::<Vec<_>>();
+    let mut faces = vec![vec![0, 5, 4, 3, 2, 1], (6..12).collect()];
+    faces.extend((0..6).map(|i| vec![i, (i + 1) % 6, (i + 1) % 6 + 6, i + 6]));
+    let step = super::Polyhedron{vtx, tri: super::fan_indexed(&faces),
+      uv: vec![], vol: 3.0, center: false};
+    assert!(step.validate(1e-9).is_valid());
+    for d in [1.0, 1.0 + 1e-10, 1.0 - 1e-10] {
+      let (a, b) = step.cut(&Plane::new([0.0, 1.0, 0.0], d), 1e-9).unwrap();
+      assert!((a.vol - 1.0).abs() < 1e-9 && (b.vol - 2.0).abs() < 1e-9);
+      assert!(a.validate(1e-9).is_valid() && b.validate(1e-9).is_valid());
+    }
+  }
+
+  #[test]
+  fn test_transform() {
+    use qm::q::{TQuaternion, Quaternion};
+    use super::slice::Plane;
+    use super::pin::Pin;
+    let bbox = |vtx: &Vec<[f64; 3]>| (0..3).map(|j| vtx.iter()
+      .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v[j]), hi.max(v[j]))))
+      .collect::<Vec<_>>();
+    let check = |ph: &polyhedron::Polyhedron<f64>, vol: f64| {
+      let (_cg, v) = ph.calc_cg_with_volume(1e-9);
+      assert!((ph.vol - vol).abs() < 1e-9 && (v - vol).abs() < 1e-9);
+      assert!(ph.validate(1e-9).is_valid());
+    };
+    let mut pin = Pin::new(1.0f64, 8, 6).ph;
+    let vol = pin.vol;
+    let b0 = bbox(&pin.vtx);
+    let q = Quaternion::<f64>::from_axis_and_angle(&[0.0, 0.0, 1.0],
+      std::f64::consts::PI / 2.0);
+    pin.rotate(&q); // lie on its side
+    let b1 = bbox(&pin.vtx);
+    assert!(((b1[0].1 - b1[0].0) - (b0[1].1 - b0[1].0)).abs() < 1e-9);
+    assert!(((b1[1].1 - b1[1].0) - (b0[0].1 - b0[0].0)).abs() < 1e-9);
+    check(&pin, vol);
+    pin.scale([2.0, 0.5, -3.0]); // mirrored
+    check(&pin, vol * 3.0);
+    pin.mirror(&Plane::new([1.0, 1.0, 0.0], 0.5));
+    check(&pin, vol * 3.0);
+    let mut cube = Cube::new(1.0f64).ph;
+    let uv = cube.uv.clone();
+    cube.mirror(&Plane::new([0.0, 0.0, 1.0], 0.0));
+    check(&cube, 8.0);
+    assert_eq!(cube.uv[0][1], [uv[0][1][0], uv[0][1][2], uv[0][1][1]]);
+    // shear translate and mirror (Vector4 [x, y, z, 1] dot_mv m)
+    let m = [
+      [1.0, 0.5, 0.0, 3.0],
+      [0.0, 2.0, 0.0, -1.0],
+      [0.0, 0.0, -1.0, 0.5],
+      [0.0, 0.0, 0.0, 1.0]];
+    cube.transform(&m);
+    check(&cube, 16.0);
+    assert!(cube.vtx.iter().any(|v| prec_eq(v, 1e-12, &[4.5, 1.0, -0.5])));
+  }
+
+  #[test]
+  fn test_csg() {
+    use qm::q::{TQuaternion, Quaternion};
+    use super::csg::CsgOp;
+    use super::error::PHError;
+    use super::cylinder::Cylinder;
+    use super::pin::Pin;
+    let a = Cube::new(1.0f64).ph; // edge 2
+    let mut b = Cube::new(1.0f64).ph;
+    b.translate(&[0.5, 0.6, 0.7]);
+    let i = 1.5 * 1.4 * 1.3;
+    for (op, vol) in [(CsgOp::Union, 16.0 - i), (CsgOp::Difference, 8.0 - i),
+      (CsgOp::Intersection, i), (CsgOp::SymmetricDifference, 16.0 - 2.0 * i)] {
+      let c = a.csg(&b, op, 1e-9).unwrap();
+      assert!((c.vol - vol).abs() < 1e-9);
+      assert_eq!(c.tri.len(), 12);
+      assert_eq!(c.uv.len(), 12);
+      let v = c.validate(1e-9);
+      assert!(v.open.is_empty() && v.inconsistent.is_empty());
+      // the two parts of the symmetric difference touch on the crossing
+      let nm = if op == CsgOp::SymmetricDifference { 18 } else { 0 };
+      assert_eq!(v.non_manifold.len(), nm);
+    }
+    // coplanar faces (touching, sharing a face and nearly coplanar)
+    for (o, vol) in [([2.0, 0.0, 0.0], 0.0), ([1.0, 0.0, 0.0], 4.0),
+      ([0.0, 0.0, 0.0], 8.0), ([1e-12, 0.0, 0.0], 8.0),
+      ([1.0, 1e-12, 1e-12], 4.0)] {
+      let mut b = Cube::new(1.0f64).ph;
+      b.translate(&o);
+      let (u, i) = (a.union(&b, 1e-9).unwrap(),
+        a.intersection(&b, 1e-9).unwrap());
+      assert!((u.vol - (16.0 - vol)).abs() < 1e-9);
+      assert!((i.vol - vol).abs() < 1e-9);
+      assert!(u.validate(1e-9).is_valid());
+      assert!(vol == 0.0 || i.validate(1e-9).is_valid()); // empty touching
+    }
+    let mut b = Cube::new(1.0f64).ph;
+    b.rotate(&Quaternion::from_axis_and_angle(&[0.0, 1.0, 0.0], 0.3));
+    b.translate(&[0.5, 0.0, 0.2]);
+    let (d, i) = (a.difference(&b, 1e-9).unwrap(),
+      a.intersection(&b, 1e-9).unwrap());
+    assert!((d.vol + i.vol - 8.0).abs() < 1e-9);
+    assert!(d.validate(1e-9).is_valid() && i.validate(1e-9).is_valid());
+    b.tri[0].clear(); // open
+    assert!(matches!(a.union(&b, 1e-9), Err(PHError::Degenerate(_))));
+    // drill a hole
+    let cyl = Cylinder::new(2.0f64, 3.0, 32).ph;
+    let mut pin = Pin::new(0.5f64, 8, 6).ph;
+    pin.translate(&[1.0, -0.5, 0.9]);
+    let d = cyl.difference(&pin, 1e-9).unwrap();
+    let i = cyl.intersection(&pin, 1e-9).unwrap();
+    let u = cyl.union(&pin, 1e-9).unwrap();
+    assert!(i.vol > 0.0 && i.vol < pin.vol);
+    assert!((d.vol + i.vol - cyl.vol).abs() < 1e-9);
+    assert!((u.vol + i.vol - cyl.vol - pin.vol).abs() < 1e-9);
+    for c in [&d, &i, &u] { assert!(c.validate(1e-9).is_valid()); }
+  }
+
+  #[test]
+  fn test_sweep() {
+    use super::sweep::{Sweep, rotation_minimising_frames, catmull_rom};
+    use super::triangulate::polygon_area;
+    use super::error::PHError;
+    let pi = std::f64::consts::PI;
+    let circle = |r: f64, n: usize| (0..n).map(|k| {
+      let th = 2.0 * pi * k as f64 / n as f64;
+      [r * th.cos(), r * th.sin()]
+    }).collect::<Vec<_>>();
+    let square = vec![[-0.5, -0.5], [0.5, -0.5], [0.5, 0.5], [-0.5, 0.5]];
+    let line = (0..=32).map(|k| [0.0, k as f64 / 16.0, 0.0])
+      .collect::<Vec<_>>();
+    let check = |ph: &polyhedron::Polyhedron<f64>, g: i64| {
+      let v = ph.validate(1e-9);
+      assert!(v.is_valid() && v.genus() == Some(g), "{:?}", v);
+      assert!(prec_eq_f(v.vol, 1e-9, ph.vol));
+    };
+    // prism, twisted, frustum (scale 1 to 0.5)
+    let prism = Sweep::new(&square, &line, (true, true), |_, _| (1.0, 0.0));
+    assert_eq!(prism.ph.tri.len(), 32 * 4 + 2);
+    assert!((prism.ph.vol - 2.0).abs() < 1e-9);
+    check(&prism.ph, 0);
+    let twist = Sweep::new(&square, &line, (true, true), |n, m|
+      (1.0, pi / 2.0 * n as f64 / (m - 1) as f64));
+    assert!(twist.ph.vol < 2.0 && twist.ph.vol > 1.95); // inside diagonals
+    check(&twist.ph, 0);
+    let frustum = Sweep::new(&square, &line, (true, true), |n, m|
+      (1.0 - 0.5 * n as f64 / (m - 1) as f64, 0.0));
+    assert!((frustum.ph.vol - 7.0 / 6.0).abs() < 1e-9);
+    check(&frustum.ph, 0);
+    // spring (helix 3 turns) and ring (closed path)
+    let helix = (0..=144).map(|k| {
+      let th = 2.0 * pi * k as f64 / 48.0;
+      [th.cos(), 0.5 * th / (2.0 * pi), th.sin()]
+    }).collect::<Vec<_>>();
+    let prof = circle(0.1, 16);
+    let fs = rotation_minimising_frames(&helix).unwrap();
+    for w in fs.windows(2) { // no rotation around the tangent
+      let [t, n, b] = w[0];
+      assert!((t.iter().zip(n.iter()).map(|(p, q)| p * q).sum::<f64>())
+        .abs() < 1e-12);
+      assert!((b.iter().zip(w[1][2].iter()).map(|(p, q)| p * q).sum::<f64>()
+        - 1.0).abs() < 1e-2);
+    }
+    let l = helix.windows(2).map(|w| (0..3).map(|j|
+      (w[1][j] - w[0][j]).powi(2)).sum::<f64>().sqrt()).sum::<f64>();
+    let spring = Sweep::new(&prof, &helix, (true, true), |_, _| (1.0, 0.0));
+    assert!((spring.ph.vol / (polygon_area(&prof) * l) - 1.0).abs() < 1e-2);
+    check(&spring.ph, 0);
+    let ring = catmull_rom(&(0..8).map(|k| {
+      let th = 2.0 * pi * k as f64 / 8.0;
+      [2.0 * th.cos(), 0.3 * (2.0 * th).sin(), 2.0 * th.sin()]
+    }).collect::<Vec<_>>(), 8, true);
+    assert_eq!((ring.len(), ring[64]), (65, ring[0])); // through the points
+    let r2 = 2.0f64.sqrt();
+    assert!(prec_eq(&f_to_f32(&ring[8]), 1e-6, &[r2 as f32, 0.3, r2 as f32]));
+    let tube = Sweep::new(&prof, &ring, (true, true), |_, _| (1.0, 0.0));
+    assert_eq!(tube.ph.tri.len(), 64 * 16);
+    check(&tube.ph, 1);
+    // errors
+    assert!(matches!(Sweep::try_new(&square[..2], &line, (true, true),
+      |_, _| (1.0, 0.0)), Err(PHError::InvalidParameter(_))));
+    let bad = vec![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    assert!(matches!(Sweep::try_new(&square, &bad, (true, true),
+      |_, _| (1.0, 0.0)), Err(PHError::Degenerate(_))));
+  }
+
+  #[test]
+  fn test_extrusion() {
+    use super::extrusion::Extrusion;
+    use super::error::PHError;
+    let sq = |c: [f64; 2], r: f64| vec![[c[0] - r, c[1] - r],
+      [c[0] + r, c[1] - r], [c[0] + r, c[1] + r], [c[0] - r, c[1] + r]];
+    let frame = Extrusion::new(&sq([3.0, 3.0], 2.0),
+      &[sq([3.0, 3.0], 1.0)], 0.5, 1.0);
+    assert_eq!(frame.ph.tri.len(), 2 + 4 + 4);
+    assert_eq!(frame.ph.vtx.len(), 16);
+    assert!((frame.ph.vol - 6.0).abs() < 1e-9);
+    assert!(frame.ph.vtx.iter().any(|v| v == &[1.0, 0.0, 1.0]));
+    let v = frame.ph.validate(1e-9);
+    assert!(v.is_valid() && v.genus() == Some(1));
+    let uv = &frame.ph.uv;
+    assert_eq!(uv.iter().map(|f| f.len()).collect::<Vec<_>>(),
+      frame.ph.tri.iter().map(|f| f.len()).collect::<Vec<_>>());
+    let within = |f: &Vec<[[f64; 2]; 3]>, lo: [f64; 2], hi: [f64; 2]|
+      f.iter().flatten().all(|q|
+        (0..2).all(|j| lo[j] <= q[j] && q[j] <= hi[j]));
+    assert!(within(&uv[0], [0.0, 0.5], [0.5, 1.0])); // bottom
+    assert!(within(&uv[1], [0.5, 0.5], [1.0, 1.0])); // top
+    assert!(uv[2..].iter().all(|f| within(f, [0.0, 0.0], [1.0, 0.5])));
+    assert_eq!((uv[2][0][0], uv[5][1][2]), ([0.0, 0.5], [1.0, 0.5])); // wrap
+    assert_eq!(frame.ph.with_uv(true).len(), frame.ph.tri.len());
+    let draft = Extrusion::new(&sq([3.0, 3.0], 2.0),
+      &[sq([3.0, 3.0], 1.0)], 2.0, 0.5);
+    assert!((draft.ph.vol - 2.0 * 12.0 * (1.0 + 0.5 + 0.25) / 3.0).abs()
+      < 1e-9);
+    assert!(draft.ph.validate(1e-9).is_valid());
+    // L shape (clockwise) with 2 holes
+    let l = vec![[0.0, 0.0], [0.0, 3.0], [1.0, 3.0], [1.0, 1.0], [3.0, 1.0],
+      [3.0, 0.0]];
+    let holes = vec![sq([0.5, 2.0], 0.25), sq([2.0, 0.5], 0.25)];
+    let ext = Extrusion::new(&l, &holes, 1.0, 1.0);
+    assert_eq!(ext.ph.tri.len(), 2 + 6 + 4 + 4);
+    assert!((ext.ph.vol - (5.0 - 0.5)).abs() < 1e-9);
+    let v = ext.ph.validate(1e-9);
+    assert!(v.is_valid() && v.genus() == Some(2));
+    assert!(matches!(Extrusion::try_new(&l, &holes, 0.0, 1.0),
+      Err(PHError::InvalidParameter(_))));
+    assert!(matches!(Extrusion::try_new(&l[..2], &[], 1.0, 1.0),
+      Err(PHError::InvalidParameter(_))));
+    for hs in [vec![sq([5.0, 5.0], 0.5)], vec![sq([2.5, 0.5], 1.0)],
+      vec![sq([0.5, 2.0], 0.25), sq([0.6, 2.1], 0.25)],
+      vec![sq([0.5, 2.0], 0.4), sq([0.5, 2.0], 0.2)]] { // out cross overlap
+      assert!(matches!(Extrusion::try_new(&l, &hs, 1.0, 1.0),
+        Err(PHError::InvalidParameter(_))));
+    }
+  }
+
+  #[test]
+  fn test_curve_tube() {
+    use super::torus::{Torus, CurveTube, Curve, FrameMode};
+    use super::error::PHError;
+    let pi = std::f64::consts::PI;
+    let len = |ph: &polyhedron::Polyhedron<f64>, r: f64| // 16-gon
+      ph.vol / (8.0 * (pi / 8.0).sin() * r * r);
+    // circle is the torus
+    let torus = Torus::new(2.0f64, 0.5, 8, 4);
+    for fm in [FrameMode::Frenet, FrameMode::ParallelTransport] {
+      let ring = CurveTube::new(0.5, 8, 4, true, fm, |t: f64| {
+        let th = 2.0 * pi * t;
+        [2.0 * th.sin(), 0.0, 2.0 * th.cos()]
+      });
+      assert_eq!(ring.ph.vtx.len(), torus.ph.vtx.len());
+      assert!((ring.ph.vol - torus.ph.vol).abs() < 1e-9);
+      let v = ring.ph.validate(1e-9);
+      assert!(v.is_valid() && v.genus() == Some(1));
+    }
+    // knots (genus 1) and helix (open ends with caps)
+    for (c, g, l) in [
+      (Curve::TorusKnot(2, 3, 2.0, 0.8), 1, 0.0),
+      (Curve::TorusKnot(3, 2, 2.0, 0.8), 1, 0.0),
+      (Curve::Trefoil(1.0), 1, 0.0),
+      (Curve::Helix(1.0, 0.6, 3.0), 0, 3.0 * (4.0 * pi * pi + 0.36).sqrt())] {
+      for fm in [FrameMode::Frenet, FrameMode::ParallelTransport] {
+        let tube = CurveTube::from_curve(c, 0.1, 64, 4, fm);
+        let v = tube.ph.validate(1e-9);
+        assert!(v.is_valid() && v.genus() == Some(g), "{:?} {:?}", c, v);
+        assert!(tube.ph.uv.iter().flatten().flatten()
+          .all(|w| w.iter().all(|&x| (0.0..=1.0).contains(&x))));
+        if l > 0.0 { assert!((len(&tube.ph, 0.1) / l - 1.0).abs() < 2e-2); }
+      }
+    }
+    let line = CurveTube::try_new(0.1, 4, 4, false, FrameMode::Frenet,
+      |t: f64| [0.0, t, 0.0]);
+    assert!(matches!(line, Err(PHError::Degenerate(_))));
+    assert!(CurveTube::try_new(0.1, 4, 4, false,
+      FrameMode::ParallelTransport, |t: f64| [0.0, t, 0.0]).is_ok());
+  }
 }
@@ -0,0 +1,138 @@
+//! Mass properties (inertia tensor) on polyhedron faces for Rust
+//!
+
+use num::Float;
+use qm::q::Quaternion;
+
+/// MassProperties
+#[derive(Debug, Clone)]
+pub struct MassProperties<F: Float> {
+  /// mass
+  pub mass: F,
+  /// volume
+  pub vol: F,
+  /// center of gravity
+  pub cg: [F; 3],
+  /// inertia tensor about cg
+  pub inertia: [[F; 3]; 3],
+  /// principal moments (ascending)
+  pub principal: [F; 3],
+  /// principal axes (rotate X Y Z axes to the axes of principal moments)
+  pub axes: Quaternion<F>
+}
+
+/// calc mass properties
+/// - idx: index of triangles on each faces
+/// - vtx: vertices (closed and counter clockwise from outside)
+/// - density: mass per volume
+pub fn calc_mass_properties<F: Float + std::fmt::Debug + std::iter::Sum>(
+  idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], density: F) -> MassProperties<F> {
+  let o = <F>::from(0).unwrap();
+  let mut m_total = o; // 6 * volume
+  let mut moi = [o, o, o];
+  let mut cov = [[o; 3]; 3]; // 120 * second moment about the origin
+  for f in idx.iter() {
+    for t in f.iter() {
+      let vs = t.map(|i| vtx[i as usize]);
+      let m = det3(&vs);
+      m_total = m_total + m;
+      let s = [0, 1, 2].map(|j| vs[0][j] + vs[1][j] + vs[2][j]);
+      for j in 0..3 {
+        moi[j] = moi[j] + m * s[j];
+        for k in 0..3 {
+          let aa = vs.iter().map(|v| v[j] * v[k]).sum::<F>();
+          cov[j][k] = cov[j][k] + m * (aa + s[j] * s[k]);
+        }
+      }
+    }
+  }
+  let vol = m_total / <F>::from(6).unwrap();
+  let mass = density * vol;
+  let cg = moi.map(|p| p / (<F>::from(4).unwrap() * m_total));
+  let q = <F>::from(120).unwrap();
+  let c = [0, 1, 2].map(|j| [0, 1, 2].map(|k|
+    density * cov[j][k] / q - mass * cg[j] * cg[k])); // parallel axis
+  let tr = c[0][0] + c[1][1] + c[2][2];
+  let inertia = [0, 1, 2].map(|j| [0, 1, 2].map(|k|
+    if j == k { tr - c[j][k] } else { -c[j][k] }));
+  let (principal, r) = eigen_sym3(&inertia);
+  MassProperties{mass, vol, cg, inertia, principal,
+    axes: quaternion_from_columns(&r)}
+}
+
+/// det of 3x3
+pub fn det3<F: Float>(m: &[[F; 3]; 3]) -> F {
+  m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+  - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+  + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// eigen values (ascending) and eigen vectors (columns) of symmetric 3x3
+/// (Jacobi rotation, right handed)
+pub fn eigen_sym3<F: Float>(m: &[[F; 3]; 3]) -> ([F; 3], [[F; 3]; 3]) {
+  let o = <F>::from(0).unwrap();
+  let l = <F>::from(1).unwrap();
+  let z = <F>::from(2).unwrap();
+  let mut a = *m;
+  let mut v = [[l, o, o], [o, l, o], [o, o, l]];
+  for _ in 0..64 {
+    let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+    let dia = a[0][0] * a[0][0] + a[1][1] * a[1][1] + a[2][2] * a[2][2];
+    if off <= F::epsilon() * F::epsilon() * dia || off == o { break; }
+    for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+      if a[p][q] == o { continue; }
+      let th = (a[q][q] - a[p][p]) / (z * a[p][q]);
+      let t = th.signum() / (th.abs() + (th * th + l).sqrt());
+      let c = l / (t * t + l).sqrt();
+      let s = t * c;
+      for ak in a.iter_mut() { // a = a J
+        let (akp, akq) = (ak[p], ak[q]);
+        ak[p] = c * akp - s * akq;
+        ak[q] = s * akp + c * akq;
+      }
+      let (ap, aq) = (a[p], a[q]); // a = J^T a
+      a[p] = [0, 1, 2].map(|k| c * ap[k] - s * aq[k]);
+      a[q] = [0, 1, 2].map(|k| s * ap[k] + c * aq[k]);
+      for vk in v.iter_mut() { // v = v J
+        let (vkp, vkq) = (vk[p], vk[q]);
+        vk[p] = c * vkp - s * vkq;
+        vk[q] = s * vkp + c * vkq;
+      }
+    }
+  }
+  let mut ord = [0, 1, 2];
+  ord.sort_by(|&i, &j| a[i][i].partial_cmp(&a[j][j]).unwrap());
+  let e = ord.map(|i| a[i][i]);
+  let mut r = [0, 1, 2].map(|k| ord.map(|i| v[k][i]));
+  if det3(&r) < o { // keep right handed
+    for rk in r.iter_mut() { rk[2] = -rk[2]; }
+  }
+  (e, r)
+}
+
+/// quaternion from rotation matrix (columns are rotated X Y Z axes)
+pub fn quaternion_from_columns<F: Float>(r: &[[F; 3]; 3]) -> Quaternion<F> {
+  let o = <F>::from(0).unwrap();
+  let l = <F>::from(1).unwrap();
+  let h = <F>::from(0.5).unwrap();
+  let tr = r[0][0] + r[1][1] + r[2][2];
+  let q4 = <F>::from(4).unwrap();
+  let q = if tr > o {
+    let s = (tr + l).sqrt() / h; // 4w
+    [s / q4,
+      (r[2][1] - r[1][2]) / s, (r[0][2] - r[2][0]) / s, (r[1][0] - r[0][1]) / s]
+  } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+    let s = (l + r[0][0] - r[1][1] - r[2][2]).sqrt() / h; // 4x
+    [(r[2][1] - r[1][2]) / s,
+      s / q4, (r[0][1] + r[1][0]) / s, (r[0][2] + r[2][0]) / s]
+  } else if r[1][1] > r[2][2] {
+    let s = (l + r[1][1] - r[0][0] - r[2][2]).sqrt() / h; // 4y
+    [(r[0][2] - r[2][0]) / s,
+      (r[0][1] + r[1][0]) / s, s / q4, (r[1][2] + r[2][1]) / s]
+  } else {
+    let s = (l + r[2][2] - r[0][0] - r[1][1]).sqrt() / h; // 4z
+    [(r[1][0] - r[0][1]) / s,
+      (r[0][2] + r[2][0]) / s, (r[1][2] + r[2][1]) / s, s / q4]
+  };
+  if q[0] < o { q.map(|p| -p) } else { q }
+}
@@ -0,0 +1,98 @@
+//! Normal (flat and smooth) on polyhedron faces for Rust
+//!
+
+use num::Float;
+use qm::v::TVector;
+
+use crate::{tri_normal, merge_vertices};
+
+/// NormalMode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalMode {
+  /// flat: one normal for each face (Vec of indexed triangles)
+  Face,
+  /// flat: one normal for each triangle
+  Triangle,
+  /// smooth: angle weighted with crease angle (radian)
+  Angle(f64),
+  /// smooth: area weighted with crease angle (radian)
+  Area(f64)
+}
+
+/// normalize (zero when length is zero)
+pub fn normalize<F: Float + std::fmt::Debug + std::iter::Sum>(v: &[F; 3]) ->
+  [F; 3] {
+  let d = v.dot(v).sqrt();
+  if d == <F>::from(0).unwrap() { return *v; }
+  [v[0] / d, v[1] / d, v[2] / d]
+}
+
+/// calc normals (parallel to uv: [n][m] Vec n faces of Vec m triangles)
+/// - idx: index of triangles on each faces
+/// - vtx: vertices
+/// - mode: NormalMode
+/// - p: precision for equality (smooth merges coincident vertices)
+pub fn calc_normals<F: Float + std::fmt::Debug + std::iter::Sum>(
+  idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], mode: NormalMode, p: F) ->
+  Vec<Vec<[[F; 3]; 3]>> {
+  let o = <F>::from(0).unwrap();
+  let vs = |t: &[u32; 3]| t.map(|i| vtx[i as usize]);
+  let tn = idx.iter().map(|f|
+    f.iter().map(|t| tri_normal(&vs(t))).collect::<Vec<_>>()
+  ).collect::<Vec<_>>();
+  match mode {
+  NormalMode::Face => idx.iter().map(|f| {
+      let n = normalize(&f.iter().fold([o, o, o], |s, t| { // area weighted
+        let v = vs(t);
+        let a = [v[1][0] - v[0][0], v[1][1] - v[0][1], v[1][2] - v[0][2]];
+        let b = [v[2][0] - v[0][0], v[2][1] - v[0][1], v[2][2] - v[0][2]];
+        let c = a.cross(&b);
+        [s[0] + c[0], s[1] + c[1], s[2] + c[2]]
+      }));
+      vec![[n, n, n]; f.len()]
+    }).collect(),
+  NormalMode::Triangle => tn.iter().map(|f|
+      f.iter().map(|&n| [n, n, n]).collect()
+    ).collect(),
+  NormalMode::Angle(c) | NormalMode::Area(c) => {
+    let angle = matches!(mode, NormalMode::Angle(_));
+    let cc = <F>::from(c.cos()).unwrap();
+    let m = merge_vertices(vtx, p);
+    // corner weight and incident corners on each merged vertex
+    let mut inc = vec![Vec::<(usize, usize, F)>::new(); vtx.len()];
+    for (fi, f) in idx.iter().enumerate() {
+      for (ti, t) in f.iter().enumerate() {
+        let v = vs(t);
+        for k in 0..3 {
+          let (a, b) = (v[(k + 1) % 3], v[(k + 2) % 3]);
+          let a = [a[0] - v[k][0], a[1] - v[k][1], a[2] - v[k][2]];
+          let b = [b[0] - v[k][0], b[1] - v[k][1], b[2] - v[k][2]];
+          let w = match angle {
+          true => {
+            let d = (a.dot(&a) * b.dot(&b)).sqrt();
+            if d == o { o }
+            else { (a.dot(&b) / d).max(-F::one()).min(F::one()).acos() }
+          },
+          false => a.cross(&b).dot(&a.cross(&b)).sqrt() / <F>::from(2).unwrap()
+          };
+          inc[m[t[k] as usize] as usize].push((fi, ti, w));
+        }
+      }
+    }
+    idx.iter().enumerate().map(|(fi, f)|
+      f.iter().enumerate().map(|(ti, t)| {
+        let ns = tn[fi][ti];
+        let degenerated = ns.dot(&ns) == o;
+        t.map(|i| {
+          normalize(&inc[m[i as usize] as usize].iter().fold([o, o, o],
+            |s, &(fj, tj, w)| {
+            let n = tn[fj][tj];
+            if !degenerated && ns.dot(&n) < cc { return s; } // crease
+            [s[0] + w * n[0], s[1] + w * n[1], s[2] + w * n[2]]
+          }))
+        })
+      }).collect()
+    ).collect()
+  }
+  }
+}
@@ -0,0 +1,101 @@
+//! Wavefront OBJ (and MTL) on polyhedron faces for Rust
+//!
+
+use std::io::Write;
+use std::path::Path;
+
+use num::Float;
+use crate::polyhedron::TUV;
+use crate::{tri_normal, f_to_f64};
+
+/// material name of the face
+/// - tf: true: the one texture (same name), false: texture each face
+pub fn mtl_name(name: &str, tf: bool, fi: usize) -> String {
+  if tf { name.to_string() } else { format!("{}_{}", name, fi) }
+}
+
+/// write obj
+/// - tf: true: the one texture, false: texture each face
+/// - mtl: Some(name) writes mtllib name.mtl and usemtl on each face
+pub fn write_obj<F, T, W>(w: &mut W, ph: &T, tf: bool, mtl: Option<&str>) ->
+  std::io::Result<()>
+  where F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized,
+  W: Write {
+  writeln!(w, "# polyhedron-faces")?;
+  if let Some(name) = mtl { writeln!(w, "mtllib {}.mtl", name)?; }
+  for v in ph.ref_vtx().iter() {
+    let v = f_to_f64(v);
+    writeln!(w, "v {} {} {}", v[0], v[1], v[2])?;
+  }
+  let phf = ph.with_uv(tf);
+  for f in phf.iter() {
+    for t in f.iter() {
+      for ftvi in t.iter() {
+        let uv = f_to_f64(&ftvi.uv);
+        writeln!(w, "vt {} {}", uv[0], 1.0 - uv[1])?; // v upward
+      }
+    }
+  }
+  for f in phf.iter() {
+    for t in f.iter() {
+      let n = f_to_f64(&tri_normal(&[t[0].p, t[1].p, t[2].p]));
+      writeln!(w, "vn {} {} {}", n[0], n[1], n[2])?;
+    }
+  }
+  let (mut kt, mut kn) = (1, 1); // 1 origin
+  for (fi, f) in phf.iter().enumerate() {
+    writeln!(w, "g face_{}", fi)?;
+    if let Some(name) = mtl {
+      writeln!(w, "usemtl {}", mtl_name(name, tf, fi))?;
+    }
+    for t in f.iter() {
+      let s = t.iter().enumerate().map(|(vi, ftvi)|
+        format!("{}/{}/{}", ftvi.idx + 1, kt + vi, kn)
+      ).collect::<Vec<_>>().join(" ");
+      writeln!(w, "f {}", s)?;
+      kt += t.len();
+      kn += 1;
+    }
+  }
+  Ok(())
+}
+
+/// write mtl (map_Kd is name.png or name_fi.png)
+/// - tf: true: the one texture, false: texture each face
+pub fn write_mtl<F, T, W>(w: &mut W, ph: &T, tf: bool, name: &str) ->
+  std::io::Result<()>
+  where F: Float, T: TUV<F> + ?Sized, W: Write {
+  writeln!(w, "# polyhedron-faces")?;
+  let n = if tf { 1 } else { ph.ref_tri().len() };
+  for fi in 0..n {
+    let m = mtl_name(name, tf, fi);
+    writeln!(w, "newmtl {}", m)?;
+    writeln!(w, "Ka 1.0 1.0 1.0")?;
+    writeln!(w, "Kd 1.0 1.0 1.0")?;
+    writeln!(w, "Ks 0.0 0.0 0.0")?;
+    writeln!(w, "d 1.0")?;
+    writeln!(w, "illum 1")?;
+    writeln!(w, "map_Kd {}.png", m)?;
+  }
+  Ok(())
+}
+
+/// save obj (and mtl with the same stem when mtl is true)
+/// - tf: true: the one texture, false: texture each face
+pub fn save_obj<F, T, P>(path: P, ph: &T, tf: bool, mtl: bool) ->
+  std::io::Result<()>
+  where F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized,
+  P: AsRef<Path> {
+  let path = path.as_ref();
+  let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("polyhedron");
+  let mut w = std::io::BufWriter::new(std::fs::File::create(path)?);
+  write_obj(&mut w, ph, tf, if mtl { Some(name) } else { None })?;
+  w.flush()?;
+  if mtl {
+    let mut w = std::io::BufWriter::new(
+      std::fs::File::create(path.with_extension("mtl"))?);
+    write_mtl(&mut w, ph, tf, name)?;
+    w.flush()?;
+  }
+  Ok(())
+}
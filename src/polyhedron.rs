@@ -4,7 +4,15 @@
 pub mod tetra;
 pub mod cube; // drawstuff(box) dxlib(cube)
 pub mod octa;
+pub mod icosa;
+pub mod dodeca;
+pub mod archimedean;
+pub mod prism;
+pub mod pyramid;
+pub mod cupola;
+pub mod johnson;
 pub mod sphere; // drawstuff dxlib
+pub mod icosphere;
 pub mod cylinder; // drawstuff
 pub mod capsule; // drawstuff dxlib
 pub mod cone; // dxlib
@@ -12,10 +20,27 @@ pub mod torus;
 pub mod pipe;
 pub mod pin;
 pub mod revolution;
+pub mod sweep;
+pub mod extrusion;
 
-use crate::{calc_cg_with_volume, translate};
+use crate::{calc_cg_with_volume, calc_edges, translate, obj, stl};
+use crate::{scale, mirror, transform, flip_winding, apply_det};
+use crate::normal::{NormalMode, calc_normals};
+use crate::mass::{MassProperties, calc_mass_properties};
+use crate::error::{PHError, tri_u16};
+use crate::conway::Conway;
+use crate::subdivision::{loop_subdivide, catmull_clark};
+use crate::weld::Weld;
+use crate::validate::{Validation, validate, fix_winding};
+use crate::query::{Bvh, Hit, ray_cast, winding_number, contains};
+use crate::query::closest_point;
+use crate::hull::convex_hull;
+use crate::slice::{Plane, Contour, slice, slice_many, cut};
+use crate::csg::{CsgOp, csg};
 
 use num::Float;
+use qm::q::{TQuaternion, Quaternion};
+use qm::m::m4::Matrix4;
 
 /// FTVI
 #[derive(Debug, Clone)]
@@ -30,6 +55,8 @@ pub struct FTVI<F: Float> {
   pub p: [F; 3],
   /// uv
   pub uv: [F; 2],
+  /// normal (None when not generated)
+  pub n: Option<[F; 3]>,
   /// index of vtx
   pub idx: usize
 }
@@ -40,11 +67,59 @@ impl<F: Float> FTVI<F> {
   pub fn puv(&self) -> (&[F; 3], &[F; 2]) {
     (&self.p, &self.uv)
   }
+  /// pn
+  pub fn pn(&self) -> (&[F; 3], Option<&[F; 3]>) {
+    (&self.p, self.n.as_ref())
+  }
 }
 
 /// PHF polyhedron face
 pub type PHF<F> = Vec<Vec<Vec<FTVI<F>>>>;
 
+/// UVT 2D affine transform of uv on the one texture
+/// (scale and rotate about the pivot, then offset)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UVT {
+  /// rotation (radian from u to v)
+  pub r: f64,
+  /// scale [u, v]
+  pub s: [f64; 2],
+  /// offset [u, v]
+  pub o: [f64; 2],
+  /// pivot [u, v]
+  pub c: [f64; 2]
+}
+
+/// UVT
+impl UVT {
+  /// construct (pivot [0, 0])
+  pub fn new(r: f64, s: [f64; 2], o: [f64; 2]) -> Self {
+    UVT{r, s, o, c: [0.0, 0.0]}
+  }
+  /// with pivot
+  pub fn pivot(self, c: [f64; 2]) -> Self {
+    UVT{c, ..self}
+  }
+  /// apply to uv
+  pub fn apply<F: Float>(&self, uv: &[F; 2]) -> [F; 2] {
+    let x = (uv[0].to_f64().unwrap() - self.c[0]) * self.s[0];
+    let y = (uv[1].to_f64().unwrap() - self.c[1]) * self.s[1];
+    let (sr, cr) = self.r.sin_cos();
+    [
+      <F>::from(x * cr - y * sr + self.c[0] + self.o[0]).unwrap(),
+      <F>::from(x * sr + y * cr + self.c[1] + self.o[1]).unwrap()]
+  }
+}
+
+/// UVT (identity)
+impl Default for UVT {
+  /// default
+  fn default() -> Self { UVT::new(0.0, [1.0, 1.0], [0.0, 0.0]) }
+}
+
+/// Edges (duplex) vertex id and sorted ids of its neighbour vertices
+pub type Edges = Vec<(u32, Vec<u32>)>;
+
 /// trait TUV
 pub trait TUV<F: Float> {
   /// get uv from each face (i: vertex id of npolygon)
@@ -68,22 +143,32 @@ pub trait TUV<F: Float> {
   /// ref vtx
   fn ref_vtx(&self) -> &Vec<[F; 3]>;
   /// ref tri
-  fn ref_tri(&self) -> &Vec<Vec<[u16; 3]>>;
+  fn ref_tri(&self) -> &Vec<Vec<[u32; 3]>>;
   /// ref uv
   fn ref_uv(&self) -> &Vec<Vec<[[F; 2]; 3]>>;
+  /// tri down conversion index u32 to u16 (Err when vertices &gt; 65536)
+  fn tri_u16(&self) -> Result<Vec<Vec<[u16; 3]>>, PHError> {
+    tri_u16(self.ref_tri())
+  }
   /// ref vtx mut
   fn ref_vtx_mut(&mut self) -> &mut Vec<[F; 3]>;
   /// ref tri mut
-  fn ref_tri_mut(&mut self) -> &mut Vec<Vec<[u16; 3]>>;
+  fn ref_tri_mut(&mut self) -> &mut Vec<Vec<[u32; 3]>>;
   /// ref uv mut
   fn ref_uv_mut(&mut self) -> &mut Vec<Vec<[[F; 2]; 3]>>;
+  /// ref vol mut (None when vol is not kept, scaled by the transforms)
+  fn ref_vol_mut(&mut self) -> Option<&mut F> { None }
   /// centered
   fn centered(&self) -> bool;
   /// with_uv
   fn with_uv(&self, tf: bool) -> PHF<F> { self.phf(tf, self.centered()) }
+  /// with_uv transformed on the one texture (uvt is ignored when tf is false)
+  fn with_uv_t(&self, tf: bool, uvt: &UVT) -> PHF<F> {
+    self.phf_t(tf, self.centered(), uvt)
+  }
   /// polyhedron faces by Vec N of Vec P(polygon) indexed triangles
   fn phf(&self, tf: bool, c: bool) -> PHF<F> {
-    if tf && self.ref_uv().len() == 0 { // will be duplex checked in get_uv_t
+    if tf && self.ref_uv().is_empty() { // will be duplex checked in get_uv_t
       println!("-- TODO: gen_uv with true expected uv but it is enpty --");
     }
     self.ref_tri().iter().enumerate().map(|(fi, f)|
@@ -94,6 +179,27 @@ pub trait TUV<F: Float> {
       ).collect()
     ).collect()
   }
+  /// phf with uv transformed on the one texture
+  /// - uvt: uv transform (ignored when tf is false)
+  fn phf_t(&self, tf: bool, c: bool, uvt: &UVT) -> PHF<F> {
+    let mut phf = self.phf(tf, c);
+    if tf {
+      for v in phf.iter_mut().flatten().flatten() { v.uv = uvt.apply(&v.uv); }
+    }
+    phf
+  }
+  /// with_uv and normal
+  fn with_normal(&self, tf: bool, mode: NormalMode, p: F) -> PHF<F>
+    where F: std::fmt::Debug + std::iter::Sum {
+    let ns = self.calc_normals(mode, p);
+    let mut phf = self.with_uv(tf);
+    for (fi, f) in phf.iter_mut().enumerate() {
+      for (ti, t) in f.iter_mut().enumerate() {
+        for (vi, v) in t.iter_mut().enumerate() { v.n = Some(ns[fi][ti][vi]); }
+      }
+    }
+    phf
+  }
   /// gen uv
   fn gen_uv(&self, i: usize, tf: bool,
     fi: usize, n: usize, ti: usize, vi: usize, c: bool) -> FTVI<F> {
@@ -105,13 +211,162 @@ pub trait TUV<F: Float> {
     true => self.get_uv_t(fi, ti, vi, 0.0f64, s, o), // on the one texture
     false => self.get_uv_f(n, ti, vi, c, r, s, o) // texture each face
     };
-    FTVI::<F>{fi, ti, vi, p, uv, idx: i}
+    FTVI::<F>{fi, ti, vi, p, uv, n: None, idx: i}
+  }
+  /// calc normals (parallel to uv: [n][m] Vec n faces of Vec m triangles)
+  /// - p: precision for equality (smooth merges coincident vertices)
+  fn calc_normals(&self, mode: NormalMode, p: F) -> Vec<Vec<[[F; 3]; 3]>>
+    where F: std::fmt::Debug + std::iter::Sum {
+    calc_normals(self.ref_tri(), self.ref_vtx(), mode, p)
+  }
+  /// calc edges (duplex)
+  /// - p: Some(precision) merges coincident vertices, None: index only
+  /// - inner: true includes the edges inside a face
+  fn calc_edges(&self, p: Option<F>, inner: bool) -> Edges {
+    calc_edges(self.ref_tri(), self.ref_vtx(), p, inner)
+  }
+  /// weld (position only topology and map from render vertices)
+  /// - p: precision for equality (same as calc_cg_f3)
+  fn weld(&self, p: F) -> Weld<F>
+    where F: std::fmt::Debug + std::iter::Sum {
+    Weld::from_polyhedron(self, p)
+  }
+  /// validate manifold watertight orientation (on the welded vertices)
+  /// - p: precision for equality (minimum area of triangle is p * p)
+  fn validate(&self, p: F) -> Validation<F> {
+    validate(self.ref_tri(), self.ref_vtx(), p)
+  }
+  /// fix winding (returns the number of flipped triangles)
+  /// (volume should be calculated again)
+  /// - p: precision for equality (minimum area of triangle is p * p)
+  fn fix_winding(&mut self, p: F) -> usize {
+    let vtx = self.ref_vtx().clone();
+    let mut tri = std::mem::take(self.ref_tri_mut());
+    let mut uv = std::mem::take(self.ref_uv_mut());
+    let n = fix_winding(&mut tri, &mut uv, &vtx, p);
+    *self.ref_tri_mut() = tri;
+    *self.ref_uv_mut() = uv;
+    n
+  }
+  /// Bvh on the triangles (for the queries below)
+  fn bvh(&self) -> Bvh<F> {
+    Bvh::new(self.ref_tri(), self.ref_vtx())
+  }
+  /// ray cast (the nearest hit with 0 &lt;= t &lt; tmax, fi ti as FTVI)
+  /// - bvh: Some(&Bvh) from bvh() or None (all triangles)
+  fn ray_cast(&self, o: &[F; 3], d: &[F; 3], tmax: F, bvh: Option<&Bvh<F>>)
+    -> Option<Hit<F>> {
+    ray_cast(self.ref_tri(), self.ref_uv(), self.ref_vtx(), bvh, o, d, tmax)
+  }
+  /// generalized winding number of the point (about 1 inside 0 outside)
+  /// - bvh: Some(&Bvh) from bvh() or None (all triangles)
+  fn winding_number(&self, q: &[F; 3], bvh: Option<&Bvh<F>>) -> F {
+    winding_number(self.ref_tri(), self.ref_vtx(), bvh, q)
+  }
+  /// point containment (winding number &gt; 0.5)
+  /// - bvh: Some(&Bvh) from bvh() or None (all triangles)
+  fn contains(&self, q: &[F; 3], bvh: Option<&Bvh<F>>) -> bool {
+    contains(self.ref_tri(), self.ref_vtx(), bvh, q)
+  }
+  /// closest point on the surface (t is the distance, fi ti as FTVI)
+  /// - bvh: Some(&Bvh) from bvh() or None (all triangles)
+  fn closest_point(&self, q: &[F; 3], bvh: Option<&Bvh<F>>) -> Option<Hit<F>> {
+    closest_point(self.ref_tri(), self.ref_uv(), self.ref_vtx(), bvh, q)
+  }
+  /// convex hull of the vertices (coplanar triangles to the one face)
+  /// - p: precision for equality (welds vertices, thickness of the faces)
+  fn convex_hull(&self, p: F) -> Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    convex_hull(self.ref_vtx(), p)
+  }
+  /// slice (closed contours on the plane)
+  /// - p: precision for equality (welds vertices, thickness of the plane)
+  fn slice(&self, plane: &Plane<F>, p: F) -> Vec<Contour<F>> {
+    slice(self.ref_tri(), self.ref_vtx(), plane, p)
+  }
+  /// slice at many distances ds along the normal n (e.g. layer heights)
+  /// - p: precision for equality (welds vertices, thickness of the plane)
+  fn slice_many(&self, n: [F; 3], ds: &[F], p: F) ->
+    Result<Vec<Vec<Contour<F>>>, PHError> {
+    slice_many(self.ref_tri(), self.ref_vtx(), n, ds, p)
+  }
+  /// cut into the two closed halves (above, below) capped on the plane
+  /// - p: precision for equality (welds vertices, thickness of the plane)
+  fn cut(&self, plane: &Plane<F>, p: F) ->
+    Result<(Polyhedron<F>, Polyhedron<F>), PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    cut(self.ref_tri(), self.ref_uv(), self.ref_vtx(), plane, p)
+  }
+  /// CSG with the other closed polyhedron (faces of other follow)
+  /// - p: precision for equality (welds vertices of each operand)
+  fn csg<T: TUV<F> + ?Sized>(&self, other: &T, op: CsgOp, p: F) ->
+    Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    csg(self, other, op, p)
+  }
+  /// union self + other
+  /// - p: precision for equality (welds vertices of each operand)
+  fn union<T: TUV<F> + ?Sized>(&self, other: &T, p: F) ->
+    Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    csg(self, other, CsgOp::Union, p)
+  }
+  /// difference self - other (e.g. drilling holes)
+  /// - p: precision for equality (welds vertices of each operand)
+  fn difference<T: TUV<F> + ?Sized>(&self, other: &T, p: F) ->
+    Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    csg(self, other, CsgOp::Difference, p)
+  }
+  /// intersection self * other
+  /// - p: precision for equality (welds vertices of each operand)
+  fn intersection<T: TUV<F> + ?Sized>(&self, other: &T, p: F) ->
+    Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    csg(self, other, CsgOp::Intersection, p)
+  }
+  /// symmetric difference (self - other) + (other - self)
+  /// - p: precision for equality (welds vertices of each operand)
+  fn symmetric_difference<T: TUV<F> + ?Sized>(&self, other: &T, p: F) ->
+    Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    csg(self, other, CsgOp::SymmetricDifference, p)
+  }
+  /// Conway operators (from right to left) e.g. "tk"
+  /// - p: precision to weld duplicate vertices
+  fn conway(&self, ops: &str, p: F) -> Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    Ok(Conway::from_polyhedron(self, p)?.apply(ops)?.to_polyhedron())
+  }
+  /// Loop subdivision n times on the triangles (carries uv)
+  /// - p: precision to weld duplicate vertices and uv
+  fn loop_subdivide(&self, n: u16, p: F) -> Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    loop_subdivide(self, n, p)
+  }
+  /// Catmull-Clark subdivision n times on the polygon of each face
+  /// (carries uv)
+  /// - p: precision to weld duplicate vertices and uv
+  fn catmull_clark(&self, n: u16, p: F) -> Result<Polyhedron<F>, PHError>
+    where F: std::fmt::Debug + std::iter::Sum {
+    catmull_clark(self, n, p)
   }
   /// calc cg with volume
   fn calc_cg_with_volume(&self, p: F) -> (Vec<F>, F)
     where F: std::fmt::Debug + std::iter::Sum {
     calc_cg_with_volume(self.ref_tri(), self.ref_vtx(), p)
   }
+  /// mass properties with density
+  fn mass_properties(&self, density: F) -> MassProperties<F>
+    where F: std::fmt::Debug + std::iter::Sum {
+    calc_mass_properties(self.ref_tri(), self.ref_vtx(), density)
+  }
+  /// mass properties with uniform mass
+  fn mass_properties_with_mass(&self, mass: F) -> MassProperties<F>
+    where F: std::fmt::Debug + std::iter::Sum {
+    let (_cg, vol) = self.calc_cg_with_volume(F::epsilon());
+    calc_mass_properties(self.ref_tri(), self.ref_vtx(), mass / vol)
+  }
   /// calc cg
   fn calc_cg(&self, p: F) -> Vec<F>
     where F: std::fmt::Debug + std::iter::Sum {
@@ -140,6 +395,71 @@ pub trait TUV<F: Float> {
   fn translate(&mut self, o: &[F]) where F: std::fmt::Debug {
     translate(self.ref_vtx_mut(), o)
   }
+  /// rotate about the origin (vol is scaled by |q|^6 when not normalized)
+  fn rotate(&mut self, q: &Quaternion<F>)
+    where F: std::fmt::Debug + std::iter::Sum {
+    self.transform(&q.to_m4_rot())
+  }
+  /// scale about the origin (flips winding when mirrored)
+  fn scale(&mut self, s: [F; 3]) {
+    scale(self.ref_vtx_mut(), &s);
+    apply_det(self, s[0] * s[1] * s[2]);
+  }
+  /// mirror on the plane (flips winding, vol is kept)
+  fn mirror(&mut self, plane: &Plane<F>) {
+    mirror(self.ref_vtx_mut(), &plane.n, plane.d);
+    apply_det(self, <F>::from(-1).unwrap());
+  }
+  /// affine transform by Matrix4 (Vector4 [x, y, z, 1] dot_mv m)
+  /// (flips winding when mirrored)
+  fn transform(&mut self, m: &Matrix4<F>)
+    where F: std::fmt::Debug + std::iter::Sum {
+    let det = transform(self.ref_vtx_mut(), m);
+    apply_det(self, det);
+  }
+  /// flip winding of all triangles (uv together, vol is kept)
+  fn flip_winding(&mut self) {
+    let mut tri = std::mem::take(self.ref_tri_mut());
+    let mut uv = std::mem::take(self.ref_uv_mut());
+    flip_winding(&mut tri, &mut uv);
+    *self.ref_tri_mut() = tri;
+    *self.ref_uv_mut() = uv;
+  }
+  /// write obj
+  /// - tf: true: the one texture, false: texture each face
+  /// - mtl: Some(name) writes mtllib name.mtl and usemtl on each face
+  fn write_obj<W: std::io::Write>(&self, w: &mut W, tf: bool,
+    mtl: Option<&str>) -> std::io::Result<()>
+    where F: std::fmt::Debug + std::iter::Sum {
+    obj::write_obj(w, self, tf, mtl)
+  }
+  /// write mtl
+  /// - tf: true: the one texture, false: texture each face
+  fn write_mtl<W: std::io::Write>(&self, w: &mut W, tf: bool, name: &str) ->
+    std::io::Result<()> {
+    obj::write_mtl(w, self, tf, name)
+  }
+  /// save obj (and mtl with the same stem when mtl is true)
+  /// - tf: true: the one texture, false: texture each face
+  fn save_obj<P: AsRef<std::path::Path>>(&self, path: P, tf: bool, mtl: bool)
+    -> std::io::Result<()> where F: std::fmt::Debug + std::iter::Sum {
+    obj::save_obj(path, self, tf, mtl)
+  }
+  /// write stl
+  /// - binary: true: binary, false: ascii
+  fn write_stl<W: std::io::Write>(&self, w: &mut W, binary: bool) ->
+    std::io::Result<()> where F: std::fmt::Debug + std::iter::Sum {
+    match binary {
+    true => stl::write_stl_binary(w, self),
+    false => stl::write_stl_ascii(w, self, "polyhedron")
+    }
+  }
+  /// save stl
+  /// - binary: true: binary, false: ascii
+  fn save_stl<P: AsRef<std::path::Path>>(&self, path: P, binary: bool) ->
+    std::io::Result<()> where F: std::fmt::Debug + std::iter::Sum {
+    stl::save_stl(path, self, binary)
+  }
 }
 
 /// Polyhedron
@@ -148,7 +468,7 @@ pub struct Polyhedron<F: Float> {
   /// vtx
   pub vtx: Vec<[F; 3]>,
   /// tri: [n][m] Vec n faces of Vec m indexed triangles
-  pub tri: Vec<Vec<[u16; 3]>>,
+  pub tri: Vec<Vec<[u32; 3]>>,
   /// uv: [n][m] Vec n faces of Vec m uv triangles
   pub uv: Vec<Vec<[[F; 2]; 3]>>,
   /// volume
@@ -161,25 +481,26 @@ pub struct Polyhedron<F: Float> {
 impl<F: Float> TUV<F> for Polyhedron<F> {
   /// get uv from the one texture (fi ti vi: id of expanded polyhedron)
   fn get_uv_t(&self, fi: usize, ti: usize, vi: usize,
-    _r: f64, _s: f64, o: [f64; 2]) -> [F; 2] { // TODO: rot scale offset
-    if self.uv.len() == 0 {
-      return [<F>::from(o[0]).unwrap(), <F>::from(o[1]).unwrap()];
-    }
-    let uv = self.uv[fi][ti][vi];
-    [<F>::from(o[0]).unwrap() + uv[0], <F>::from(o[1]).unwrap() + uv[1]]
+    r: f64, s: f64, o: [f64; 2]) -> [F; 2] { // rot scale offset
+    let uvt = UVT::new(r, [s, s], o);
+    let z = <F>::from(0).unwrap();
+    if self.uv.is_empty() { return uvt.apply(&[z, z]); }
+    uvt.apply(&self.uv[fi][ti][vi])
   }
   /// ref vtx
   fn ref_vtx(&self) -> &Vec<[F; 3]> { &self.vtx }
   /// ref tri
-  fn ref_tri(&self) -> &Vec<Vec<[u16; 3]>> { &self.tri }
+  fn ref_tri(&self) -> &Vec<Vec<[u32; 3]>> { &self.tri }
   /// ref uv
   fn ref_uv(&self) -> &Vec<Vec<[[F; 2]; 3]>> { &self.uv }
   /// ref vtx mut
   fn ref_vtx_mut(&mut self) -> &mut Vec<[F; 3]> { &mut self.vtx }
   /// ref tri mut
-  fn ref_tri_mut(&mut self) -> &mut Vec<Vec<[u16; 3]>> { &mut self.tri }
+  fn ref_tri_mut(&mut self) -> &mut Vec<Vec<[u32; 3]>> { &mut self.tri }
   /// ref uv mut
   fn ref_uv_mut(&mut self) -> &mut Vec<Vec<[[F; 2]; 3]>> { &mut self.uv }
+  /// ref vol mut
+  fn ref_vol_mut(&mut self) -> Option<&mut F> { Some(&mut self.vol) }
   /// centered
   fn centered(&self) -> bool { self.center }
 }
@@ -0,0 +1,234 @@
+//! Archimedean solids on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_cg_with_volume, calc_edges, fan_indexed};
+use crate::{polygons_from_edges, norm_f3};
+use crate::{Polyhedron, Edges, center_indexed};
+use crate::error::{PHError, check_positive};
+
+/// Solid (vertex configuration)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Solid {
+  /// truncated tetrahedron 3.6.6
+  TruncatedTetrahedron,
+  /// cuboctahedron 3.4.3.4
+  Cuboctahedron,
+  /// truncated cube 3.8.8
+  TruncatedCube,
+  /// truncated octahedron 4.6.6
+  TruncatedOctahedron,
+  /// rhombicuboctahedron 3.4.4.4
+  Rhombicuboctahedron,
+  /// truncated cuboctahedron 4.6.8
+  TruncatedCuboctahedron,
+  /// snub cube 3.3.3.3.4
+  SnubCube,
+  /// icosidodecahedron 3.5.3.5
+  Icosidodecahedron,
+  /// truncated dodecahedron 3.10.10
+  TruncatedDodecahedron,
+  /// truncated icosahedron 5.6.6
+  TruncatedIcosahedron,
+  /// rhombicosidodecahedron 3.4.5.4
+  Rhombicosidodecahedron,
+  /// truncated icosidodecahedron 4.6.10
+  TruncatedIcosidodecahedron,
+  /// snub dodecahedron 3.3.3.3.5
+  SnubDodecahedron
+}
+
+/// Solid
+impl Solid {
+  /// all 13 solids
+  pub const ALL: [Solid; 13] = [
+    Solid::TruncatedTetrahedron, Solid::Cuboctahedron, Solid::TruncatedCube,
+    Solid::TruncatedOctahedron, Solid::Rhombicuboctahedron,
+    Solid::TruncatedCuboctahedron, Solid::SnubCube, Solid::Icosidodecahedron,
+    Solid::TruncatedDodecahedron, Solid::TruncatedIcosahedron,
+    Solid::Rhombicosidodecahedron, Solid::TruncatedIcosidodecahedron,
+    Solid::SnubDodecahedron];
+
+  /// vertices (exact coordinates before scaling to the circumradius 1)
+  pub fn vertices(&self) -> Vec<[f64; 3]> {
+    let g = (1.0f64 + 5.0f64.sqrt()) / 2.0f64; // golden ratio 1.618...
+    let s = 2.0f64.sqrt();
+    match self {
+    Solid::TruncatedTetrahedron =>
+      pts(&[[3.0, 1.0, 1.0]], Perm::All, Sign::EvenMinus),
+    Solid::Cuboctahedron => pts(&[[1.0, 1.0, 0.0]], Perm::All, Sign::All),
+    Solid::TruncatedCube =>
+      pts(&[[s - 1.0, 1.0, 1.0]], Perm::All, Sign::All),
+    Solid::TruncatedOctahedron =>
+      pts(&[[0.0, 1.0, 2.0]], Perm::All, Sign::All),
+    Solid::Rhombicuboctahedron =>
+      pts(&[[1.0, 1.0, 1.0 + s]], Perm::All, Sign::All),
+    Solid::TruncatedCuboctahedron =>
+      pts(&[[1.0, 1.0 + s, 1.0 + 2.0 * s]], Perm::All, Sign::All),
+    Solid::SnubCube => {
+      let t = (1.0 + (19.0 - 3.0 * 33.0f64.sqrt()).cbrt()
+        + (19.0 + 3.0 * 33.0f64.sqrt()).cbrt()) / 3.0; // tribonacci
+      let b = [[1.0, 1.0 / t, t]];
+      let mut v = pts(&b, Perm::Even, Sign::EvenPlus);
+      v.extend(pts(&b, Perm::Odd, Sign::OddPlus));
+      v
+    },
+    Solid::Icosidodecahedron => pts(&[
+      [0.0, 0.0, g], [0.5, g / 2.0, g * g / 2.0]], Perm::Even, Sign::All),
+    Solid::TruncatedDodecahedron => pts(&[
+      [0.0, 1.0 / g, 2.0 + g], [1.0 / g, g, 2.0 * g], [g, 2.0, g + 1.0]],
+      Perm::Even, Sign::All),
+    Solid::TruncatedIcosahedron => pts(&[
+      [0.0, 1.0, 3.0 * g], [1.0, 2.0 + g, 2.0 * g], [g, 2.0, g * g * g]],
+      Perm::Even, Sign::All),
+    Solid::Rhombicosidodecahedron => pts(&[
+      [1.0, 1.0, g * g * g], [g * g, g, 2.0 * g], [2.0 + g, 0.0, g * g]],
+      Perm::Even, Sign::All),
+    Solid::TruncatedIcosidodecahedron => pts(&[
+      [1.0 / g, 1.0 / g, 3.0 + g], [2.0 / g, g, 1.0 + 2.0 * g],
+      [1.0 / g, g * g, 3.0 * g - 1.0], [2.0 * g - 1.0, 2.0, 2.0 + g],
+      [g, 3.0, 2.0 * g]], Perm::Even, Sign::All),
+    Solid::SnubDodecahedron => {
+      let x = sol_snub(g); // x^3 - 2x = g
+      let (a, b) = (x - 1.0 / x, x * g + g * g + g / x);
+      let mut v = pts(&[
+        [2.0 * a, 2.0, 2.0 * b],
+        [a + b / g + g, -a * g + b + 1.0 / g, a / g + b * g - 1.0],
+        [a + b / g - g, a * g - b + 1.0 / g, a / g + b * g + 1.0]],
+        Perm::Even, Sign::EvenPlus);
+      v.extend(pts(&[
+        [-a / g + b * g - 1.0, -a + b / g + g, a * g + b + 1.0 / g],
+        [-a / g + b * g + 1.0, a - b / g + g, a * g + b - 1.0 / g]],
+        Perm::Even, Sign::OddPlus));
+      v
+    }
+    }
+  }
+
+  /// polygons (counter clockwise from outside) on the circumradius 1
+  pub fn polygons(&self) -> (Vec<[f64; 3]>, Vec<Vec<u32>>) {
+    let vs = self.vertices();
+    let r = vs.iter().map(norm_f3).fold(0.0f64, f64::max);
+    let vtx = vs.iter().map(|v| v.map(|p| p / r)).collect::<Vec<_>>();
+    let faces = polygons_from_edges(&vtx);
+    (vtx, faces)
+  }
+}
+
+/// permutation of the coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Perm { All, Even, Odd }
+
+/// signs of the coordinates (number of minus or plus)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sign { All, EvenMinus, EvenPlus, OddPlus }
+
+/// points by permutations and signs (coincident points are skipped)
+fn pts(bs: &[[f64; 3]], perm: Perm, sign: Sign) -> Vec<[f64; 3]> {
+  let even = [[0, 1, 2], [1, 2, 0], [2, 0, 1]];
+  let odd = [[1, 0, 2], [0, 2, 1], [2, 1, 0]];
+  let ps = match perm {
+  Perm::All => [even, odd].concat(),
+  Perm::Even => even.to_vec(),
+  Perm::Odd => odd.to_vec()
+  };
+  let mut v = Vec::<[f64; 3]>::new();
+  for b in bs.iter() {
+    for p in ps.iter() {
+      for m in 0..8 {
+        let s = [0, 1, 2].map(|k| if m & (1 << k) != 0 { -1.0 } else { 1.0 });
+        let nm = s.iter().filter(|&&q| q < 0.0).count();
+        let ok = match sign {
+        Sign::All => true,
+        Sign::EvenMinus => nm % 2 == 0,
+        Sign::EvenPlus => (3 - nm) % 2 == 0,
+        Sign::OddPlus => (3 - nm) % 2 == 1
+        };
+        if !ok { continue; }
+        let q = [0, 1, 2].map(|k| s[k] * b[p[k]]);
+        if v.iter().any(|w| (0..3).all(|k| (w[k] - q[k]).abs() < 1e-9)) {
+          continue; // zero with both signs
+        }
+        v.push(q);
+      }
+    }
+  }
+  v
+}
+
+/// real root of x^3 - 2x = g (Newton)
+fn sol_snub(g: f64) -> f64 {
+  let mut x = 1.7f64;
+  for _ in 0..32 { x -= (x * x * x - 2.0 * x - g) / (3.0 * x * x - 2.0); }
+  x
+}
+
+/// Archimedean
+#[derive(Debug)]
+pub struct Archimedean<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Archimedean
+impl<F: Float + std::fmt::Debug> Archimedean<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(solid: Solid, r: F) -> Self {
+    Self::try_new(solid, r).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed sphere
+  pub fn try_new(solid: Solid, r: F) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    let (vs, fs) = solid.polygons();
+    let vtx = vs.iter().map(|v| v.map(|p| r * <F>::from(p).unwrap()))
+      .collect::<Vec<_>>();
+    let tri = fan_indexed(&fs);
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(Archimedean{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false},
+      edges})
+  }
+}
+
+/// ArchimedeanCenter
+#[derive(Debug)]
+pub struct ArchimedeanCenter<F: Float> {
+  /// polyhedron tri: Vec n of Vec m indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// ArchimedeanCenter
+impl<F: Float + std::fmt::Debug> ArchimedeanCenter<F>
+  where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(solid: Solid, r: F) -> Self {
+    Self::try_new(solid, r).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed sphere
+  pub fn try_new(solid: Solid, r: F) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    let (vs, fs) = solid.polygons();
+    let mut vtx = vs.iter().map(|v| v.map(|p| r * <F>::from(p).unwrap()))
+      .collect::<Vec<_>>();
+    let n = vtx.len() as u32;
+    for f in fs.iter() {
+      let c = center_indexed(f, &vtx);
+      vtx.push(c);
+    }
+    let tri = fs.iter().enumerate().map(|(i, f)| {
+      let o = i as u32 + n;
+      (0..f.len()).map(|k| [o, f[k], f[(k + 1) % f.len()]]).collect()
+    }).collect::<Vec<_>>();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(ArchimedeanCenter{ph: Polyhedron{vtx, tri, uv: vec![], vol,
+      center: true}, edges})
+  }
+}
@@ -3,8 +3,10 @@
 
 use num::Float;
 
-use crate::calc_cg_with_volume;
-use crate::Polyhedron;
+use crate::{calc_cg_with_volume, calc_edges, uv_indexed, cap_uv};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_quality};
+use crate::error::{check_positive, check_non_negative};
 
 /// Capsule
 #[derive(Debug)]
@@ -12,42 +14,70 @@ pub struct Capsule<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// Capsule
 impl<F: Float + std::fmt::Debug> Capsule<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F, l: F, q: u16) -> Self {
+    Self::try_new(r, l, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F, l: F, q: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_non_negative("l", l)?;
+    check_quality("q", q)?;
     let h = l / <F>::from(2).unwrap();
-    let s = q * 2 + 1; // middle = q
+    let q = q as u32;
     let c = q * 4;
-    let vtx = (0..s+1).into_iter().flat_map(|sn| { // duplex middle q (s+1)
-      let ns = if sn <= q { sn } else { sn - 1 };
-      let sth = std::f64::consts::PI * ns as f64 / (s - 1) as f64; // 0 to =pi
-      (0..c).into_iter().map(|cn| {
+    let cc = c + 1; // duplex seam (cn = c is cn = 0)
+    // rings (ns, part, top) part 0: bottom disc, 1: side, 2: top disc
+    let rings = (0..=q).map(|ns| (ns, 0, false))
+      .chain([(q, 1, false), (q, 1, true)])
+      .chain((q..=q*2).map(|ns| (ns, 2, true)))
+      .collect::<Vec<_>>();
+    check_index(rings.len() as u64 * cc as u64)?;
+    let vtx = rings.iter().flat_map(|&(ns, _part, top)| {
+      let sth = std::f64::consts::PI * ns as f64 / (q * 2) as f64; // 0 to =pi
+      (0..cc).map(move |cn| {
         let cth = 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
         let w = r * <F>::from(sth.sin()).unwrap();
         [
           w * <F>::from(cth.sin()).unwrap(),
-          r * (<F>::from(-sth.cos()).unwrap() + if sn <= q { -h } else { h }),
+          r * (<F>::from(-sth.cos()).unwrap() + if top { h } else { -h }),
           w * <F>::from(cth.cos()).unwrap()]
-      }).collect::<Vec<_>>()
+      })
+    }).collect::<Vec<_>>();
+    let uvs = rings.iter().flat_map(|&(ns, part, top)| {
+      (0..cc).map(move |cn| {
+        let cth = 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
+        match part {
+        0 => cap_uv(cth, ns as f64 / q as f64, false),
+        2 => cap_uv(cth, (q * 2 - ns) as f64 / q as f64, true),
+        _ => [<F>::from(cn as f64 / c as f64).unwrap(), // side upper half
+          <F>::from(if top { 0.0 } else { 0.5 }).unwrap()]
+        }
+      })
     }).collect::<Vec<_>>();
-    let tri = (0..s).into_iter().flat_map(|sn| { // always below (s+1-1)
-      (0..c).into_iter().map(|cn| {
-        let k = sn * c + cn; // always below
-        let ks = k + c; // not over
-        let mut ksc = k + c + 1;
-        if ksc >= (sn + 2) * c { ksc -= c; } // not over
-        let mut kc = k + 1;
-        if kc >= (sn + 1) * c { kc -= c; } // not over
-        // println!("[{} {} {} {}]", k, kc, ksc, ks);
-        vec![[k, kc, ksc], [k, ksc, ks]]
-      }).collect::<Vec<_>>()
+    let nr = rings.len() as u32;
+    let tri = (0..rings.len()-1).filter(|&sn|
+      rings[sn].1 == rings[sn + 1].1).flat_map(|sn| {
+      let sn = sn as u32;
+      (0..c).map(move |cn| {
+        let k = sn * cc + cn; // always below
+        let (kc, ks, ksc) = (k + 1, k + cc, k + cc + 1); // not over
+        match sn { // skip degenerated triangles on the poles
+        0 => vec![[k, ksc, ks]],
+        _ if sn == nr - 2 => vec![[k, kc, ksc]],
+        _ => vec![[k, kc, ksc], [k, ksc, ks]]
+        }
+      })
     }).collect::<Vec<_>>();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Capsule{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let uv = uv_indexed(&tri, &uvs);
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Capsule{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
   }
 }
@@ -3,8 +3,9 @@
 
 use num::Float;
 
-use crate::calc_cg_with_volume;
-use crate::Polyhedron;
+use crate::{calc_cg_with_volume, calc_edges, uv_indexed, cap_uv};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_quality};
 
 /// Cone
 #[derive(Debug)]
@@ -12,29 +13,51 @@ pub struct Cone<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 4])>
+  pub edges: Edges
 }
 
 /// Cone
 impl<F: Float + std::fmt::Debug> Cone<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F, h: F, q: u16) -> Self {
+    Self::try_new(r, h, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F, h: F, q: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("h", h)?;
+    check_quality("q", q)?;
     let o = <F>::from(0).unwrap();
     let b = h / <F>::from(-4.0).unwrap();
     let t = h * <F>::from(3.0 / 4.0).unwrap();
-    let c = q * 4;
-    let mut vtx = (0..c).into_iter().map(|cn| {
-      let th = 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
-      [r * <F>::from(th.sin()).unwrap(), b, r * <F>::from(th.cos()).unwrap()]
-    }).collect::<Vec<_>>();
-    vtx.push([o, r * b, o]); // [c] bottom
-    vtx.push([o, r * t, o]); // [c + 1] top
-    let tri = (0..c).into_iter().flat_map(|cn| {
+    let c = q as u32 * 4;
+    let cc = c + 1; // duplex seam (cn = c is cn = 0)
+    let (ka, rb, cb) = (cc, cc + c, cc + 2 * c); // apex, bottom rim, center
+    check_index(cc as u64 + 2 * c as u64 + 1)?;
+    let th = |cn: f64| 2.0 * std::f64::consts::PI * cn / c as f64;
+    let rim = |cn: f64| [
+      r * <F>::from(th(cn).sin()).unwrap(), b,
+      r * <F>::from(th(cn).cos()).unwrap()];
+    let mut vtx = (0..cc).map(|cn| // len = cc
+      rim(cn as f64)).collect::<Vec<_>>();
+    vtx.extend((0..c).map(|_| [o, r * t, o])); // [ka] top
+    vtx.extend((0..c).map(|cn| rim(cn as f64))); // [rb] bottom
+    vtx.push([o, r * b, o]); // [cb] bottom
+    let half = <F>::from(0.5).unwrap();
+    let mut uvs = (0..cc).map(|cn| // side upper half
+      [<F>::from(cn as f64 / c as f64).unwrap(), half]).collect::<Vec<_>>();
+    uvs.extend((0..c).map(|cn|
+      [<F>::from((cn as f64 + 0.5) / c as f64).unwrap(), o])); // apex
+    uvs.extend((0..c).map(|cn| cap_uv(th(cn as f64), 1.0, false)));
+    uvs.push(cap_uv(0.0, 0.0, false));
+    let tri = (0..c).flat_map(|cn| {
       let ck = (cn + 1) % c;
-      vec![vec![[c, ck, cn]], vec![[c + 1, cn, ck]]] // bottom, top
+      vec![vec![[cb, rb + ck, rb + cn]], vec![[ka + cn, cn, cn + 1]]] // b, t
     }).collect::<Vec<_>>();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Cone{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let uv = uv_indexed(&tri, &uvs);
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Cone{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
   }
 }
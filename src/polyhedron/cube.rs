@@ -3,8 +3,9 @@
 
 use num::Float;
 
-use crate::calc_cg_with_volume;
-use crate::{Polyhedron, center_indexed, center_indexed_uv};
+use crate::{calc_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges, center_indexed, center_indexed_uv};
+use crate::error::{PHError, check_positive};
 
 /// Cuboid
 #[derive(Debug)]
@@ -12,13 +13,18 @@ pub struct Cuboid<F: Float> {
   /// polyhedron tri: Vec 6 of Vec 2 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 3])>
+  pub edges: Edges
 }
 
 /// Cuboid
 impl<F: Float + std::fmt::Debug> Cuboid<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(lxyz: [F; 3]) -> Self {
+    Self::try_new(lxyz).unwrap()
+  }
+  /// construct
+  pub fn try_new(lxyz: [F; 3]) -> Result<Self, PHError> {
+    for v in lxyz.iter() { check_positive("lxyz", *v)?; }
     let h = <F>::from(2).unwrap();
     let (x, y, z) = (lxyz[0] / h, lxyz[1] / h, lxyz[2] / h);
     let vtx = vec![
@@ -29,10 +35,10 @@ impl<F: Float + std::fmt::Debug> Cuboid<F> where F: std::iter::Sum {
       [-x, y, z], [-x, -y, z], [x, -y, z], [x, y, z], // +Z (0 0 1) top
       [-x, y, -z], [x, y, -z], [x, -y, -z], [-x, -y, -z] // -Z (0 0 -1) bottom
     ];
-    let tri = (0..6).into_iter().map(|f| {
+    let tri = (0..6).map(|f| {
       let k = f * 4;
       vec![[k, k + 1, k + 2], [k, k + 2, k + 3]]
-    }).collect();
+    }).collect::<Vec<_>>();
     let uv = vec![
 /*
       [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]], // +X (1 0 0) right
@@ -49,7 +55,7 @@ impl<F: Float + std::fmt::Debug> Cuboid<F> where F: std::iter::Sum {
       [[0.00, 0.25], [0.00, 0.50], [0.25, 0.50], [0.25, 0.25]], // 6457
       [[0.75, 0.75], [0.75, 0.50], [0.50, 0.50], [0.50, 0.75]] // 2310
     ].into_iter().map(|f|
-      (0..2).into_iter().map(|t| {
+      (0..2).map(|t| {
         let i = [[0, 1, 2], [0, 2, 3]];
         i[t].into_iter().map(|k|
           f[k].iter().map(|&p|
@@ -58,9 +64,10 @@ impl<F: Float + std::fmt::Debug> Cuboid<F> where F: std::iter::Sum {
         ).collect::<Vec<_>>().try_into().unwrap()
       }).collect()
     ).collect();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Cuboid{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges}
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Cuboid{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
   }
 }
 
@@ -70,13 +77,18 @@ pub struct Cube<F: Float> {
   /// polyhedron tri: Vec 6 of Vec 2 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 3])>
+  pub edges: Edges
 }
 
 /// Cube
 impl<F: Float + std::fmt::Debug> Cube<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F) -> Self {
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    check_positive("r", r)?;
     let vtx = vec![
       [r, -r, r], [r, -r, -r], [r, r, -r], [r, r, r], // +X (1 0 0) right
       [-r, -r, r], [-r, r, r], [-r, r, -r], [-r, -r, -r], // -X (-1 0 0) left
@@ -85,10 +97,10 @@ impl<F: Float + std::fmt::Debug> Cube<F> where F: std::iter::Sum {
       [-r, r, r], [-r, -r, r], [r, -r, r], [r, r, r], // +Z (0 0 1) top
       [-r, r, -r], [r, r, -r], [r, -r, -r], [-r, -r, -r] // -Z (0 0 -1) bottom
     ];
-    let tri = (0..6).into_iter().map(|f| {
+    let tri = (0..6).map(|f| {
       let k = f * 4;
       vec![[k, k + 1, k + 2], [k, k + 2, k + 3]]
-    }).collect();
+    }).collect::<Vec<_>>();
     let uv = vec![
 /*
       [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]], // +X (1 0 0) right
@@ -105,7 +117,7 @@ impl<F: Float + std::fmt::Debug> Cube<F> where F: std::iter::Sum {
       [[0.00, 0.25], [0.00, 0.50], [0.25, 0.50], [0.25, 0.25]], // 6457
       [[0.75, 0.75], [0.75, 0.50], [0.50, 0.50], [0.50, 0.75]] // 2310
     ].into_iter().map(|f|
-      (0..2).into_iter().map(|t| {
+      (0..2).map(|t| {
         let i = [[0, 1, 2], [0, 2, 3]];
         i[t].into_iter().map(|k|
           f[k].iter().map(|&p|
@@ -114,9 +126,10 @@ impl<F: Float + std::fmt::Debug> Cube<F> where F: std::iter::Sum {
         ).collect::<Vec<_>>().try_into().unwrap()
       }).collect()
     ).collect();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Cube{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges}
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Cube{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
   }
 }
 
@@ -126,28 +139,32 @@ pub struct CubeCenter<F: Float> {
   /// polyhedron tri: Vec 6 of Vec 4 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 3])>
+  pub edges: Edges
 }
 
 /// CubeCenter
 impl<F: Float + std::fmt::Debug> CubeCenter<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F) -> Self {
-    let cube = Cube::<F>::new(r);
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    let cube = Cube::<F>::try_new(r)?;
     let mut vtx = cube.ph.vtx.clone();
     for f in 0..6 {
-      let idxs = (0..4).into_iter().map(|i| f * 4 + i).collect::<Vec<_>>();
+      let idxs = (0..4).map(|i| f * 4 + i).collect::<Vec<_>>();
       vtx.push(center_indexed(&idxs, &cube.ph.vtx));
     }
-    let tri = (0..6).into_iter().map(|f| {
+    let tri = (0..6).map(|f| {
       let o = f + 24;
       let k = f * 4;
       vec![[o, k, k + 1], [o, k + 1, k + 2], [o, k + 2, k + 3], [o, k + 3, k]]
-    }).collect();
-    let uv = (0..6).into_iter().map(|f| {
+    }).collect::<Vec<_>>();
+    let uv = (0..6).map(|f| {
       let uvr = &cube.ph.uv[f]; // [[0 1 2] [0 2 3]]
       let uvs = vec![uvr[0][0], uvr[0][1], uvr[0][2], uvr[1][2]]; // [0 1 2 3]
-      let idxs = (0..4).into_iter().collect::<Vec<_>>();
+      let idxs = (0..4).collect::<Vec<_>>();
       let o = center_indexed_uv(&idxs, &uvs);
       vec![
         [o, uvs[0], uvs[1]],
@@ -155,8 +172,9 @@ impl<F: Float + std::fmt::Debug> CubeCenter<F> where F: std::iter::Sum {
         [o, uvs[2], uvs[3]],
         [o, uvs[3], uvs[0]]]
     }).collect();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    CubeCenter{ph: Polyhedron{vtx, tri, uv, vol, center: true}, edges}
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(CubeCenter{ph: Polyhedron{vtx, tri, uv, vol, center: true}, edges})
   }
 }
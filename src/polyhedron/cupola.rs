@@ -0,0 +1,121 @@
+//! Cupola and Rotunda (n-gon) on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_edges, polygon_xz, polyhedron_from_polygons};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_sides};
+
+/// radius of the top n-gon that has the same sides as the base 2n-gon
+/// - r: radius of circumscribed circle of the base 2n-gon
+/// - n: number of sides of the top n-gon
+pub fn cupola_top(r: f64, n: u32) -> f64 {
+  let pi = std::f64::consts::PI;
+  r * (pi / (2 * n) as f64).sin() / (pi / n as f64).sin()
+}
+
+/// (radius, height) of the top and middle n-gon of the rotunda
+/// (all vertices on the sphere of the base 2n-gon,
+///  the top n-gon and the upper triangles have the same sides as the base)
+/// - r: radius of circumscribed circle of the base 2n-gon
+/// - n: number of sides of the top n-gon
+pub fn rotunda_rings(r: f64, n: u32) -> [(f64, f64); 2] {
+  let pi = std::f64::consts::PI;
+  let a = 2.0 * r * (pi / (2 * n) as f64).sin();
+  let rt = cupola_top(r, n);
+  let yt = (r * r - rt * rt).sqrt();
+  // |top - middle| = a with the middle vertex on the sphere
+  let c = rt * (pi / n as f64).cos();
+  let k = r * r - a * a / 2.0;
+  let d = yt.atan2(c) - (k / (r * c.hypot(yt))).acos();
+  [(rt, yt), (r * d.cos(), r * d.sin())]
+}
+
+/// Cupola
+#[derive(Debug)]
+pub struct Cupola<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Cupola
+impl<F: Float + std::fmt::Debug> Cupola<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, h: F, n: u16) -> Self {
+    Self::try_new(r, h, n).unwrap()
+  }
+  /// construct (top n-gon, n triangles, n rectangles and base 2n-gon)
+  /// - r: radius of circumscribed circle of the base 2n-gon
+  /// - h: height
+  /// - n: number of sides of the top n-gon
+  pub fn try_new(r: F, h: F, n: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("h", h)?;
+    check_sides("n", n)?;
+    let n = n as u32;
+    check_index(n as u64 * 3)?;
+    let o = <F>::from(0).unwrap();
+    let rt = <F>::from(cupola_top(1.0, n)).unwrap() * r;
+    let th = std::f64::consts::PI / (2 * n) as f64;
+    let mut vtx = polygon_xz(r, o, 0.0, 2 * n); // base
+    vtx.extend(polygon_xz(rt, h, th, n)); // top
+    let t = 2 * n;
+    let mut faces = vec![(0..t).rev().collect(), (t..t+n).collect()];
+    faces.extend((0..n).flat_map(|i| {
+      let (b, bn, tn) = (2 * i + 1, (2 * i + 2) % t, t + (i + 1) % n);
+      [vec![2 * i, b, t + i], vec![b, bn, tn, t + i]]
+    }));
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Cupola{ph, edges})
+  }
+}
+
+/// Rotunda
+#[derive(Debug)]
+pub struct Rotunda<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Rotunda
+impl<F: Float + std::fmt::Debug> Rotunda<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, n: u16) -> Self {
+    Self::try_new(r, n).unwrap()
+  }
+  /// construct (top n-gon, 2n triangles, n pentagons and base 2n-gon)
+  /// n = 5 is the pentagonal rotunda (half of icosidodecahedron)
+  /// - r: radius of circumscribed sphere (circle of the base 2n-gon)
+  /// - n: number of sides of the top n-gon
+  pub fn try_new(r: F, n: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_sides("n", n)?;
+    let n = n as u32;
+    check_index(n as u64 * 4)?;
+    let o = <F>::from(0).unwrap();
+    let [(rt, yt), (rm, ym)] = rotunda_rings(1.0, n);
+    let f = |p: f64| <F>::from(p).unwrap() * r;
+    let th = std::f64::consts::PI / (2 * n) as f64;
+    let mut vtx = polygon_xz(r, o, 0.0, 2 * n); // base
+    vtx.extend(polygon_xz(f(rm), f(ym), th, n)); // middle
+    vtx.extend(polygon_xz(f(rt), f(yt), -th, n)); // top
+    let (b, m, t) = (2 * n, 2 * n, 3 * n);
+    let mut faces = vec![(0..b).rev().collect(), (t..t+n).collect()];
+    faces.extend((0..n).flat_map(|i| {
+      let (ip, in_) = ((i + n - 1) % n, (i + 1) % n);
+      [
+        vec![m + i, t + in_, t + i], // upper
+        vec![(2 * i + b - 1) % b, 2 * i, m + i, t + i, m + ip], // pentagon
+        vec![2 * i, 2 * i + 1, m + i]] // lower
+    }));
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Rotunda{ph, edges})
+  }
+}
@@ -3,8 +3,9 @@
 
 use num::Float;
 
-use crate::calc_cg_with_volume;
-use crate::Polyhedron;
+use crate::{calc_cg_with_volume, calc_edges, uv_indexed, cap_uv};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_quality};
 
 /// Cylinder
 #[derive(Debug)]
@@ -12,39 +13,60 @@ pub struct Cylinder<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 5])>
+  pub edges: Edges
 }
 
 /// Cylinder
 impl<F: Float + std::fmt::Debug> Cylinder<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F, l: F, q: u16) -> Self {
+    Self::try_new(r, l, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F, l: F, q: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("l", l)?;
+    check_quality("q", q)?;
     let o = <F>::from(0).unwrap();
     let h = l / <F>::from(2).unwrap();
-    let c = q * 4;
-    let c2 = 2 * c;
-    let mut vtx = (0..c).into_iter().flat_map(|cn| { // len = c2
-      let th = 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
-      let (cx, _cy, cz) = (
-        r * <F>::from(th.sin()).unwrap(),
-        o,
-        r * <F>::from(th.cos()).unwrap());
-      vec![[cx, -r * h, cz], [cx, r * h, cz]]
+    let c = q as u32 * 4;
+    let c2 = 2 * (c + 1); // duplex seam (cn = c is cn = 0)
+    let (rb, rt) = (c2, c2 + c); // cap rims
+    let (cb, ct) = (c2 + 2 * c, c2 + 2 * c + 1); // cap centers
+    check_index(c2 as u64 + 2 * c as u64 + 2)?;
+    let th = |cn: u32| 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
+    let rim = |cn: u32, y: F| [
+      r * <F>::from(th(cn).sin()).unwrap(), y,
+      r * <F>::from(th(cn).cos()).unwrap()];
+    let mut vtx = (0..=c).flat_map(|cn| // len = c2
+      vec![rim(cn, -r * h), rim(cn, r * h)]).collect::<Vec<_>>();
+    vtx.extend((0..c).map(|cn| rim(cn, -r * h))); // [rb] bottom
+    vtx.extend((0..c).map(|cn| rim(cn, r * h))); // [rt] top
+    vtx.push([o, -r * h, o]); // [cb] bottom
+    vtx.push([o, r * h, o]); // [ct] top
+    let mut uvs = (0..=c).flat_map(|cn| { // side upper half
+      let u = <F>::from(cn as f64 / c as f64).unwrap();
+      vec![[u, <F>::from(0.5).unwrap()], [u, o]]
     }).collect::<Vec<_>>();
-    vtx.push([o, -r * h, o]); // [c2] bottom
-    vtx.push([o, r * h, o]); // [c2 + 1] top
-    let tri = (0..c).into_iter().flat_map(|cn| {
+    uvs.extend((0..c).map(|cn| cap_uv(th(cn), 1.0, false)));
+    uvs.extend((0..c).map(|cn| cap_uv(th(cn), 1.0, true)));
+    uvs.push(cap_uv(0.0, 0.0, false));
+    uvs.push(cap_uv(0.0, 0.0, true));
+    let tri = (0..c).flat_map(|cn| {
       let kb = 2 * cn; // not over
       let kt = kb + 1; // not over
-      let ktc = (kt + 2) % c2; // not over
-      let kbc = (kb + 2) % c2; // not over
+      let (ktc, kbc) = (kt + 2, kb + 2); // not over
+      let ck = (cn + 1) % c;
       vec![
-        vec![[c2, kbc, kb]], // bottom
+        vec![[cb, rb + ck, rb + cn]], // bottom
         vec![[kb, kbc, ktc], [kb, ktc, kt]], // side
-        vec![[c2 + 1, kt, ktc]]] // top
+        vec![[ct, rt + cn, rt + ck]]] // top
     }).collect::<Vec<_>>();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Cylinder{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let uv = uv_indexed(&tri, &uvs);
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Cylinder{ph: Polyhedron{vtx, tri, uv, vol, center: false},
+      edges})
   }
 }
@@ -0,0 +1,101 @@
+//! Dodeca on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges, center_indexed};
+use crate::error::{PHError, check_positive};
+
+/// pentagons (counter clockwise from outside)
+const PENTAGONS: [[u32; 5]; 12] = [
+  [4, 8, 0, 12, 14],
+  [1, 12, 0, 16, 18],
+  [2, 16, 0, 8, 10],
+  [14, 12, 1, 9, 5],
+  [18, 16, 2, 13, 3],
+  [10, 8, 4, 17, 6],
+  [15, 13, 2, 10, 6],
+  [19, 17, 4, 14, 5],
+  [11, 9, 1, 18, 3],
+  [7, 11, 3, 13, 15],
+  [7, 15, 6, 17, 19],
+  [7, 19, 5, 9, 11]];
+
+/// Dodeca
+#[derive(Debug)]
+pub struct Dodeca<F: Float> {
+  /// polyhedron tri: Vec 12 of Vec 3 indexed triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Dodeca
+impl<F: Float + std::fmt::Debug> Dodeca<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F) -> Self {
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed sphere
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    let g = (1.0f64 + 5.0f64.sqrt()) / 2.0f64; // golden ratio 1.618...
+    let a = 1.0f64 / 3.0f64.sqrt(); // 0.5773...
+    let b = a / g; // 0.3568...
+    let c = a * g; // 0.9341...
+    let o = 0.0f64;
+    let vtx = vec![
+      [a, a, a], [a, a, -a], [a, -a, a], [a, -a, -a], // cube +X
+      [-a, a, a], [-a, a, -a], [-a, -a, a], [-a, -a, -a], // cube -X
+      [o, b, c], [o, b, -c], [o, -b, c], [o, -b, -c], // YZ
+      [b, c, o], [b, -c, o], [-b, c, o], [-b, -c, o], // XY
+      [c, o, b], [-c, o, b], [c, o, -b], [-c, o, -b] // ZX
+    ].into_iter().map(|v|
+      v.into_iter().map(|f|
+        r * <F>::from(f).unwrap()
+      ).collect::<Vec<_>>().try_into().unwrap()
+    ).collect::<Vec<_>>();
+    let tri = PENTAGONS.iter().map(|f| {
+      vec![[f[0], f[1], f[2]], [f[0], f[2], f[3]], [f[0], f[3], f[4]]]
+    }).collect::<Vec<_>>();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(Dodeca{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges})
+  }
+}
+
+/// DodecaCenter
+#[derive(Debug)]
+pub struct DodecaCenter<F: Float> {
+  /// polyhedron tri: Vec 12 of Vec 5 indexed triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// DodecaCenter
+impl<F: Float + std::fmt::Debug> DodecaCenter<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F) -> Self {
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed sphere
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    let dodeca = Dodeca::<F>::try_new(r)?;
+    let mut vtx = dodeca.ph.vtx.clone();
+    for f in PENTAGONS.iter() {
+      vtx.push(center_indexed(f, &dodeca.ph.vtx));
+    }
+    let tri = PENTAGONS.iter().enumerate().map(|(i, f)| {
+      let o = i as u32 + 20;
+      (0..5).map(|k| [o, f[k], f[(k + 1) % 5]]).collect()
+    }).collect::<Vec<_>>();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(DodecaCenter{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: true},
+      edges})
+  }
+}
@@ -0,0 +1,140 @@
+//! Extrusion (polygon with holes) on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_cg_with_volume, calc_edges, cap_uv};
+use crate::{Polyhedron, Edges};
+use crate::triangulate::{polygon_area, point_in_polygon, triangulate};
+use crate::error::{PHError, check_index, check_positive, check_volume};
+
+/// Extrusion
+#[derive(Debug)]
+pub struct Extrusion<F: Float> {
+  /// polyhedron tri: Vec n of Vec m indexed triangles
+  /// (bottom, top, side walls of the outer then the holes)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Extrusion
+impl<F: Float + std::fmt::Debug> Extrusion<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(outer: &[[F; 2]], holes: &[Vec<[F; 2]>], h: F, s: F) ->
+    Self {
+    Self::try_new(outer, holes, h, s).unwrap()
+  }
+  /// construct (bottom on y = 0 and top on y = h keeping x z)
+  /// - outer: outer polygon [x, z] (either direction)
+  /// - holes: holes inside the outer polygon [x, z] (either direction)
+  /// - h: height
+  /// - s: scale of the top around the centroid of the outer (draft)
+  pub fn try_new(outer: &[[F; 2]], holes: &[Vec<[F; 2]>], h: F, s: F) ->
+    Result<Self, PHError> {
+    check_positive("h", h)?;
+    check_positive("s", s)?;
+    if outer.len() < 3 || holes.iter().any(|p| p.len() < 3) {
+      return Err(PHError::InvalidParameter(
+        "polygons must have >= 3 points".to_string()));
+    }
+    let o = <F>::from(0).unwrap();
+    let mut ps = vec![outer.to_vec()];
+    ps.extend(holes.iter().cloned());
+    for (i, p) in ps.iter_mut().enumerate() {
+      let a = polygon_area(p);
+      if a == o || !a.is_finite() {
+        return Err(PHError::Degenerate("polygon has no area".to_string()));
+      }
+      if (a > o) == (i > 0) { p.reverse(); } // outer positive holes negative
+    }
+    for (i, p) in ps.iter().enumerate().skip(1) {
+      let inside = p.iter().all(|q| point_in_polygon(q, &ps[0]))
+        && !crossing(p, &ps[0]);
+      let disjoint = ps[1..i].iter().all(|r| !crossing(p, r)
+        && !point_in_polygon(&p[0], r) && !point_in_polygon(&r[0], p));
+      if !(inside && disjoint) {
+        return Err(PHError::InvalidParameter(
+          "holes must be inside the outer polygon and disjoint".to_string()));
+      }
+    }
+    let n = ps.iter().map(|p| p.len()).sum::<usize>() as u32;
+    check_index(n as u64 * 2)?;
+    let (cx, cz) = centroid(&ps[0]);
+    let mut vtx = ps.iter().flatten().map(|q| [q[0], o, q[1]])
+      .collect::<Vec<_>>(); // bottom
+    vtx.extend(ps.iter().flatten().map(|q|
+      [cx + (q[0] - cx) * s, h, cz + (q[1] - cz) * s])); // top
+    let ts = triangulate(&ps[0], &ps[1..]); // counter clockwise seen from -Y
+    let mut tri = vec![ts.clone(), ts.iter().map(|t|
+      [n + t[0], n + t[2], n + t[1]]).collect()];
+    let pts = ps.iter().flatten().collect::<Vec<_>>();
+    let r = pts.iter().fold(o, |r, q| r.max((q[0] - cx).hypot(q[1] - cz)));
+    let cuv = |i: u32, top: bool| { // planar on the cap disc
+      let q = pts[i as usize];
+      let (x, z) = ((q[0] - cx) / r, (q[1] - cz) / r);
+      cap_uv(x.to_f64().unwrap().atan2(z.to_f64().unwrap()),
+        x.hypot(z).to_f64().unwrap(), top)
+    };
+    let mut uv = vec![
+      ts.iter().map(|t| t.map(|i| cuv(i, false))).collect(),
+      ts.iter().map(|t| [t[0], t[2], t[1]].map(|i| cuv(i, true))).collect()];
+    let hv = <F>::from(0.5).unwrap(); // side upper half (u wraps each)
+    let mut b = 0;
+    for p in ps.iter() {
+      let m = p.len() as u32;
+      let u = arc(p);
+      for k in 0..m {
+        let (i, j) = (b + k, b + (k + 1) % m);
+        let (ui, uj) = (u[k as usize], u[k as usize + 1]);
+        tri.push(vec![[i, n + i, n + j], [i, n + j, j]]);
+        uv.push(vec![[[ui, hv], [ui, o], [uj, o]],
+          [[ui, hv], [uj, o], [uj, hv]]]);
+      }
+      b += m;
+    }
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx,
+      <F>::from(1e-6).unwrap());
+    check_volume(vol)?;
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(Extrusion{ph: Polyhedron{vtx, tri, uv, vol, center: false},
+      edges})
+  }
+}
+
+/// centroid of the polygon (area)
+fn centroid<F: Float>(p: &[[F; 2]]) -> (F, F) {
+  let o = <F>::from(0).unwrap();
+  let (mut x, mut z) = (o, o);
+  for i in 0..p.len() {
+    let (a, b) = (p[i], p[(i + 1) % p.len()]);
+    let c = a[0] * b[1] - b[0] * a[1];
+    x = x + (a[0] + b[0]) * c;
+    z = z + (a[1] + b[1]) * c;
+  }
+  let k = <F>::from(6).unwrap() * polygon_area(p);
+  (x / k, z / k)
+}
+
+/// arc length ratio along the closed polygon (0 to 1, len + 1 points)
+fn arc<F: Float>(p: &[[F; 2]]) -> Vec<F> {
+  let mut a = vec![<F>::from(0).unwrap()];
+  for (q, r) in p.iter().zip(p.iter().cycle().skip(1)) {
+    a.push(a[a.len() - 1] + (r[0] - q[0]).hypot(r[1] - q[1]));
+  }
+  let l = a[p.len()];
+  a.iter().map(|&x| x / l).collect()
+}
+
+/// edges of the closed polygons intersect (touching included)
+fn crossing<F: Float>(p: &[[F; 2]], q: &[[F; 2]]) -> bool {
+  let o = <F>::from(0).unwrap();
+  let s = |a: &[F; 2], b: &[F; 2], c: &[F; 2]|
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+  let seg = |a: &[F; 2], b: &[F; 2], c: &[F; 2], d: &[F; 2]|
+    (0..2).all(|j| a[j].min(b[j]) <= c[j].max(d[j])
+      && c[j].min(d[j]) <= a[j].max(b[j]))
+    && s(a, b, c) * s(a, b, d) <= o && s(c, d, a) * s(c, d, b) <= o;
+  p.iter().zip(p.iter().cycle().skip(1)).any(|(a, b)|
+    q.iter().zip(q.iter().cycle().skip(1)).any(|(c, d)| seg(a, b, c, d)))
+}
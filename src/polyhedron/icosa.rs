@@ -0,0 +1,68 @@
+//! Icosa on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_positive};
+
+/// Icosa
+#[derive(Debug)]
+pub struct Icosa<F: Float> {
+  /// polyhedron tri: Vec 20 of Vec 1 indexed triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Icosa
+impl<F: Float + std::fmt::Debug> Icosa<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F) -> Self {
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed sphere
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    let g = (1.0f64 + 5.0f64.sqrt()) / 2.0f64; // golden ratio 1.618...
+    let a = 1.0f64 / (1.0f64 + g * g).sqrt(); // 0.5257...
+    let b = g * a; // 0.8507...
+    let o = 0.0f64;
+    let vtx = vec![
+      [o, a, b], [o, a, -b], [o, -a, b], [o, -a, -b], // YZ
+      [a, b, o], [a, -b, o], [-a, b, o], [-a, -b, o], // XY
+      [b, o, a], [-b, o, a], [b, o, -a], [-b, o, -a] // ZX
+    ].into_iter().map(|v|
+      v.into_iter().map(|f|
+        r * <F>::from(f).unwrap()
+      ).collect::<Vec<_>>().try_into().unwrap()
+    ).collect::<Vec<_>>();
+    let tri = vec![
+      vec![[0, 2, 8]],
+      vec![[0, 9, 2]],
+      vec![[0, 4, 6]],
+      vec![[0, 8, 4]],
+      vec![[0, 6, 9]],
+      vec![[1, 10, 3]],
+      vec![[1, 3, 11]],
+      vec![[1, 6, 4]],
+      vec![[1, 4, 10]],
+      vec![[1, 11, 6]],
+      vec![[2, 7, 5]],
+      vec![[2, 5, 8]],
+      vec![[2, 9, 7]],
+      vec![[3, 5, 7]],
+      vec![[3, 10, 5]],
+      vec![[3, 7, 11]],
+      vec![[4, 8, 10]],
+      vec![[5, 10, 8]],
+      vec![[6, 11, 9]],
+      vec![[7, 9, 11]]
+    ];
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(Icosa{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges})
+  }
+}
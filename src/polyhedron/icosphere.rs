@@ -0,0 +1,121 @@
+//! IcoSphere (geodesic) on polyhedron faces for Rust
+//!
+
+use std::collections::BTreeMap;
+
+use num::Float;
+
+use crate::{calc_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges, icosa::Icosa};
+use crate::error::{PHError, check_index, check_positive, check_quality};
+
+/// Class geodesic pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+  /// Class I {3,5+}f,0 (f * f triangles on each icosa face)
+  I,
+  /// Class II {3,5+}f,f (3 * f * f triangles on each icosa face)
+  II
+}
+
+/// Key of the vertex shared along icosa corners and edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+  /// icosa corner
+  V(u32),
+  /// on the icosa edge (a &lt; b, step from a)
+  E(u32, u32, u32),
+  /// inside the icosa face (face, i, j)
+  F(usize, u32, u32)
+}
+
+/// IcoSphere
+#[derive(Debug)]
+pub struct IcoSphere<F: Float> {
+  /// polyhedron tri: Vec n of Vec 1 indexed triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// IcoSphere
+impl<F: Float + std::fmt::Debug> IcoSphere<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, f: u16, class: Class) -> Self {
+    Self::try_new(r, f, class).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed sphere
+  /// - f: frequency (divisions of each icosa edge)
+  /// - class: Class I or Class II
+  pub fn try_new(r: F, f: u16, class: Class) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_quality("f", f)?;
+    let n = f as u64 * f as u64 * 10 + 2;
+    check_index(match class { Class::I => n, Class::II => n * 3 - 4 })?;
+    let f = f as u32;
+    let icosa = Icosa::<f64>::new(1.0);
+    let key = |fi: usize, t: &[u32; 3], i: u32, j: u32| {
+      let edge = |a: u32, b: u32, s: u32| match a < b {
+        true => Key::E(a, b, s),
+        false => Key::E(b, a, f - s)
+      };
+      match (i, j) {
+      (0, 0) => Key::V(t[0]),
+      _ if i == f => Key::V(t[1]),
+      _ if j == f => Key::V(t[2]),
+      (_, 0) => edge(t[0], t[1], i),
+      (0, _) => edge(t[0], t[2], j),
+      _ if i + j == f => edge(t[1], t[2], j),
+      _ => Key::F(fi, i, j)
+      }
+    };
+    // Class I on the flat icosa faces
+    let mut ids = BTreeMap::<Key, u32>::new();
+    let mut flat = Vec::<[f64; 3]>::new();
+    let mut tri = Vec::<[u32; 3]>::new();
+    for (fi, ft) in icosa.ph.tri.iter().enumerate() {
+      let t = &ft[0];
+      let [a, b, c] = t.map(|k| icosa.ph.vtx[k as usize]);
+      let mut id = |i: u32, j: u32| *ids.entry(key(fi, t, i, j))
+        .or_insert_with(|| {
+        let (u, v) = (i as f64 / f as f64, j as f64 / f as f64);
+        flat.push([0, 1, 2].map(|k|
+          a[k] + (b[k] - a[k]) * u + (c[k] - a[k]) * v));
+        flat.len() as u32 - 1
+      });
+      for i in 0..f {
+        for j in 0..f-i {
+          tri.push([id(i, j), id(i + 1, j), id(i, j + 1)]); // up
+          if i + j + 1 < f {
+            tri.push([id(i + 1, j), id(i + 1, j + 1), id(i, j + 1)]); // down
+          }
+        }
+      }
+    }
+    if class == Class::II { // sqrt3: kis and flip the edges of Class I
+      let k = flat.len() as u32;
+      let cs = tri.iter().map(|t| [0, 1, 2].map(|j|
+        t.iter().map(|&i| flat[i as usize][j]).sum::<f64>() / 3.0))
+        .collect::<Vec<_>>();
+      flat.extend(cs);
+      let mut es = BTreeMap::<(u32, u32), u32>::new(); // directed edge: tri
+      for (ti, t) in tri.iter().enumerate() {
+        for m in 0..3 { es.insert((t[m], t[(m + 1) % 3]), ti as u32); }
+      }
+      tri = es.iter().filter(|(&(a, b), _)| a < b).flat_map(|(&(a, b), &t1)| {
+        let t2 = es[&(b, a)];
+        [[a, k + t2, k + t1], [b, k + t1, k + t2]]
+      }).collect();
+    }
+    let vtx = flat.iter().map(|v| {
+      let d = v.iter().map(|p| p * p).sum::<f64>().sqrt();
+      v.map(|p| r * <F>::from(p / d).unwrap())
+    }).collect::<Vec<_>>();
+    let tri = tri.into_iter().map(|t| vec![t]).collect::<Vec<_>>();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(IcoSphere{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false},
+      edges})
+  }
+}
@@ -0,0 +1,288 @@
+//! Johnson solids (built from pyramid, cupola, rotunda, prism and antiprism)
+//! on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_edges, polygon_xz, polyhedron_from_polygons};
+use crate::{Polyhedron, Edges, polygons_from_edges};
+use crate::cupola::{cupola_top, rotunda_rings};
+use crate::error::{PHError, check_positive};
+
+/// cap on the end of the body
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cap { Flat, Pyramid, Cupola, Rotunda }
+
+/// body between the caps
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Body { None, Prism, Antiprism }
+
+/// Johnson solid (number Jn)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Solid {
+  /// J1
+  SquarePyramid,
+  /// J2
+  PentagonalPyramid,
+  /// J3
+  TriangularCupola,
+  /// J4
+  SquareCupola,
+  /// J5
+  PentagonalCupola,
+  /// J6
+  PentagonalRotunda,
+  /// J7
+  ElongatedTriangularPyramid,
+  /// J8
+  ElongatedSquarePyramid,
+  /// J9
+  ElongatedPentagonalPyramid,
+  /// J10
+  GyroelongatedSquarePyramid,
+  /// J11
+  GyroelongatedPentagonalPyramid,
+  /// J12
+  TriangularBipyramid,
+  /// J13
+  PentagonalBipyramid,
+  /// J14
+  ElongatedTriangularBipyramid,
+  /// J15
+  ElongatedSquareBipyramid,
+  /// J16
+  ElongatedPentagonalBipyramid,
+  /// J17
+  GyroelongatedSquareBipyramid,
+  /// J18
+  ElongatedTriangularCupola,
+  /// J19
+  ElongatedSquareCupola,
+  /// J20
+  ElongatedPentagonalCupola,
+  /// J21
+  ElongatedPentagonalRotunda,
+  /// J22
+  GyroelongatedTriangularCupola,
+  /// J23
+  GyroelongatedSquareCupola,
+  /// J24
+  GyroelongatedPentagonalCupola,
+  /// J25
+  GyroelongatedPentagonalRotunda,
+  /// J27
+  TriangularOrthobicupola,
+  /// J28
+  SquareOrthobicupola,
+  /// J29
+  SquareGyrobicupola,
+  /// J30
+  PentagonalOrthobicupola,
+  /// J31
+  PentagonalGyrobicupola,
+  /// J32
+  PentagonalOrthocupolarotunda,
+  /// J33
+  PentagonalGyrocupolarotunda,
+  /// J34
+  PentagonalOrthobirotunda,
+  /// J35
+  ElongatedTriangularOrthobicupola,
+  /// J36
+  ElongatedTriangularGyrobicupola,
+  /// J37
+  ElongatedSquareGyrobicupola,
+  /// J38
+  ElongatedPentagonalOrthobicupola,
+  /// J39
+  ElongatedPentagonalGyrobicupola,
+  /// J40
+  ElongatedPentagonalOrthocupolarotunda,
+  /// J41
+  ElongatedPentagonalGyrocupolarotunda,
+  /// J42
+  ElongatedPentagonalOrthobirotunda,
+  /// J43
+  ElongatedPentagonalGyrobirotunda
+}
+
+/// Johnson solid
+impl Solid {
+  /// all solids
+  pub const ALL: [Solid; 42] = [
+    Solid::SquarePyramid, Solid::PentagonalPyramid,
+    Solid::TriangularCupola, Solid::SquareCupola, Solid::PentagonalCupola,
+    Solid::PentagonalRotunda,
+    Solid::ElongatedTriangularPyramid, Solid::ElongatedSquarePyramid,
+    Solid::ElongatedPentagonalPyramid,
+    Solid::GyroelongatedSquarePyramid, Solid::GyroelongatedPentagonalPyramid,
+    Solid::TriangularBipyramid, Solid::PentagonalBipyramid,
+    Solid::ElongatedTriangularBipyramid, Solid::ElongatedSquareBipyramid,
+    Solid::ElongatedPentagonalBipyramid, Solid::GyroelongatedSquareBipyramid,
+    Solid::ElongatedTriangularCupola, Solid::ElongatedSquareCupola,
+    Solid::ElongatedPentagonalCupola, Solid::ElongatedPentagonalRotunda,
+    Solid::GyroelongatedTriangularCupola, Solid::GyroelongatedSquareCupola,
+    Solid::GyroelongatedPentagonalCupola,
+    Solid::GyroelongatedPentagonalRotunda,
+    Solid::TriangularOrthobicupola,
+    Solid::SquareOrthobicupola, Solid::SquareGyrobicupola,
+    Solid::PentagonalOrthobicupola, Solid::PentagonalGyrobicupola,
+    Solid::PentagonalOrthocupolarotunda, Solid::PentagonalGyrocupolarotunda,
+    Solid::PentagonalOrthobirotunda,
+    Solid::ElongatedTriangularOrthobicupola,
+    Solid::ElongatedTriangularGyrobicupola,
+    Solid::ElongatedSquareGyrobicupola,
+    Solid::ElongatedPentagonalOrthobicupola,
+    Solid::ElongatedPentagonalGyrobicupola,
+    Solid::ElongatedPentagonalOrthocupolarotunda,
+    Solid::ElongatedPentagonalGyrocupolarotunda,
+    Solid::ElongatedPentagonalOrthobirotunda,
+    Solid::ElongatedPentagonalGyrobirotunda];
+
+  /// number Jn
+  pub fn number(&self) -> u32 {
+    let i = Solid::ALL.iter().position(|s| s == self).unwrap() as u32 + 1;
+    if i > 25 { i + 1 } else { i } // J26 gyrobifastigium is not stacked
+  }
+
+  /// (sides of the body n-gon, bottom, body, top, gyro)
+  fn stack(&self) -> (u32, Cap, Body, Cap, bool) {
+    use {Cap::*, Body::{None as No, Prism, Antiprism}};
+    match self {
+    Solid::SquarePyramid => (4, Flat, No, Pyramid, false),
+    Solid::PentagonalPyramid => (5, Flat, No, Pyramid, false),
+    Solid::TriangularCupola => (6, Flat, No, Cupola, false),
+    Solid::SquareCupola => (8, Flat, No, Cupola, false),
+    Solid::PentagonalCupola => (10, Flat, No, Cupola, false),
+    Solid::PentagonalRotunda => (10, Flat, No, Rotunda, false),
+    Solid::ElongatedTriangularPyramid => (3, Flat, Prism, Pyramid, false),
+    Solid::ElongatedSquarePyramid => (4, Flat, Prism, Pyramid, false),
+    Solid::ElongatedPentagonalPyramid => (5, Flat, Prism, Pyramid, false),
+    Solid::GyroelongatedSquarePyramid => (4, Flat, Antiprism, Pyramid, false),
+    Solid::GyroelongatedPentagonalPyramid =>
+      (5, Flat, Antiprism, Pyramid, false),
+    Solid::TriangularBipyramid => (3, Pyramid, No, Pyramid, false),
+    Solid::PentagonalBipyramid => (5, Pyramid, No, Pyramid, false),
+    Solid::ElongatedTriangularBipyramid => (3, Pyramid, Prism, Pyramid, false),
+    Solid::ElongatedSquareBipyramid => (4, Pyramid, Prism, Pyramid, false),
+    Solid::ElongatedPentagonalBipyramid => (5, Pyramid, Prism, Pyramid, false),
+    Solid::GyroelongatedSquareBipyramid =>
+      (4, Pyramid, Antiprism, Pyramid, false),
+    Solid::ElongatedTriangularCupola => (6, Flat, Prism, Cupola, false),
+    Solid::ElongatedSquareCupola => (8, Flat, Prism, Cupola, false),
+    Solid::ElongatedPentagonalCupola => (10, Flat, Prism, Cupola, false),
+    Solid::ElongatedPentagonalRotunda => (10, Flat, Prism, Rotunda, false),
+    Solid::GyroelongatedTriangularCupola => (6, Flat, Antiprism, Cupola, false),
+    Solid::GyroelongatedSquareCupola => (8, Flat, Antiprism, Cupola, false),
+    Solid::GyroelongatedPentagonalCupola =>
+      (10, Flat, Antiprism, Cupola, false),
+    Solid::GyroelongatedPentagonalRotunda =>
+      (10, Flat, Antiprism, Rotunda, false),
+    Solid::TriangularOrthobicupola => (6, Cupola, No, Cupola, false),
+    Solid::SquareOrthobicupola => (8, Cupola, No, Cupola, false),
+    Solid::SquareGyrobicupola => (8, Cupola, No, Cupola, true),
+    Solid::PentagonalOrthobicupola => (10, Cupola, No, Cupola, false),
+    Solid::PentagonalGyrobicupola => (10, Cupola, No, Cupola, true),
+    Solid::PentagonalOrthocupolarotunda => (10, Cupola, No, Rotunda, false),
+    Solid::PentagonalGyrocupolarotunda => (10, Cupola, No, Rotunda, true),
+    Solid::PentagonalOrthobirotunda => (10, Rotunda, No, Rotunda, false),
+    Solid::ElongatedTriangularOrthobicupola =>
+      (6, Cupola, Prism, Cupola, false),
+    Solid::ElongatedTriangularGyrobicupola => (6, Cupola, Prism, Cupola, true),
+    Solid::ElongatedSquareGyrobicupola => (8, Cupola, Prism, Cupola, true),
+    Solid::ElongatedPentagonalOrthobicupola =>
+      (10, Cupola, Prism, Cupola, false),
+    Solid::ElongatedPentagonalGyrobicupola =>
+      (10, Cupola, Prism, Cupola, true),
+    Solid::ElongatedPentagonalOrthocupolarotunda =>
+      (10, Cupola, Prism, Rotunda, false),
+    Solid::ElongatedPentagonalGyrocupolarotunda =>
+      (10, Cupola, Prism, Rotunda, true),
+    Solid::ElongatedPentagonalOrthobirotunda =>
+      (10, Rotunda, Prism, Rotunda, false),
+    Solid::ElongatedPentagonalGyrobirotunda =>
+      (10, Rotunda, Prism, Rotunda, true)
+    }
+  }
+
+  /// vertices (edge length 1)
+  pub fn vertices(&self) -> Vec<[f64; 3]> {
+    let pi = std::f64::consts::PI;
+    let (n, bottom, body, top, gyro) = self.stack();
+    let r = 0.5 / (pi / n as f64).sin();
+    // height and phase of the top n-gon of the body
+    let (h, ph) = match body {
+    Body::None => (0.0, 0.0),
+    Body::Prism => (1.0, 0.0),
+    Body::Antiprism => {
+      let d = 2.0 * r * (pi / (2 * n) as f64).sin(); // horizontal
+      ((1.0 - d * d).sqrt(), pi / n as f64)
+    }
+    };
+    let mut vtx = polygon_xz(r, 0.0, 0.0, n);
+    if body != Body::None { vtx.extend(polygon_xz(r, h, ph, n)); }
+    // cap on the n-gon at y (s: +1 upward, -1 downward, g: gyro)
+    let cap = |c: Cap, y: f64, s: f64, ph: f64, g: bool| {
+      let m = n / 2; // top m-gon of the cupola or the rotunda
+      let th = pi / n as f64;
+      let g = if g { 2.0 * th } else { 0.0 };
+      match c {
+      Cap::Flat => vec![],
+      Cap::Pyramid => vec![[0.0, y + s * (1.0 - r * r).sqrt(), 0.0]],
+      Cap::Cupola => {
+        let rt = cupola_top(r, m);
+        let w = r * th.cos() - rt * (2.0 * th).cos(); // inradius difference
+        polygon_xz(rt, y + s * (1.0 - w * w).sqrt(), ph + th + g, m)
+      },
+      Cap::Rotunda => {
+        let [(rt, yt), (rm, ym)] = rotunda_rings(r, m);
+        let mut v = polygon_xz(rm, y + s * ym, ph + th + g, m);
+        v.extend(polygon_xz(rt, y + s * yt, ph - th + g, m));
+        v
+      }
+      }
+    };
+    vtx.extend(cap(bottom, 0.0, -1.0, 0.0, false));
+    vtx.extend(cap(top, h, 1.0, ph, gyro));
+    vtx
+  }
+
+  /// polygons (counter clockwise from outside) with edge length 1
+  pub fn polygons(&self) -> (Vec<[f64; 3]>, Vec<Vec<u32>>) {
+    let vs = self.vertices();
+    let n = vs.len() as f64;
+    let c = [0, 1, 2].map(|k| vs.iter().map(|v| v[k]).sum::<f64>() / n);
+    let vtx = vs.iter().map(|v| [0, 1, 2].map(|k| v[k] - c[k]))
+      .collect::<Vec<_>>();
+    let faces = polygons_from_edges(&vtx);
+    (vtx, faces)
+  }
+}
+
+/// Johnson
+#[derive(Debug)]
+pub struct Johnson<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Johnson
+impl<F: Float + std::fmt::Debug> Johnson<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(solid: Solid, a: F) -> Self {
+    Self::try_new(solid, a).unwrap()
+  }
+  /// construct
+  /// - a: edge length
+  pub fn try_new(solid: Solid, a: F) -> Result<Self, PHError> {
+    check_positive("a", a)?;
+    let (vs, faces) = solid.polygons();
+    let vtx = vs.iter().map(|v| v.map(|p| a * <F>::from(p).unwrap()))
+      .collect::<Vec<_>>();
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Johnson{ph, edges})
+  }
+}
@@ -3,8 +3,9 @@
 
 use num::Float;
 
-use crate::calc_cg_with_volume;
-use crate::{Polyhedron, cube::CubeCenter};
+use crate::{calc_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges, cube::CubeCenter};
+use crate::error::PHError;
 
 /// Octa
 #[derive(Debug)]
@@ -12,15 +13,19 @@ pub struct Octa<F: Float> {
   /// polyhedron tri: Vec 8 of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 4])>
+  pub edges: Edges
 }
 
 /// Octa
 impl<F: Float + std::fmt::Debug> Octa<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F) -> Self {
-    let cubec = CubeCenter::<F>::new(r);
-    let vtx = (24..24+6).into_iter().map(|i| cubec.ph.vtx[i]).collect();
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    let cubec = CubeCenter::<F>::try_new(r)?;
+    let vtx = (24..24+6).map(|i| cubec.ph.vtx[i]).collect::<Vec<_>>();
     let tri = vec![
       vec![[0, 2, 4]],
       vec![[0, 4, 3]],
@@ -32,7 +37,7 @@ impl<F: Float + std::fmt::Debug> Octa<F> where F: std::iter::Sum {
       vec![[1, 3, 4]]
     ];
     let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Octa{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(Octa{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges})
   }
 }
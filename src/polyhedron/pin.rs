@@ -3,8 +3,8 @@
 
 use num::Float;
 
-use crate::{prec_eq, f_to_f32};
-use crate::{Polyhedron, revolution::Revolution, calc_cg, calc_cg_f2_x};
+use crate::{Polyhedron, Edges, revolution::Revolution, calc_cg_f2_x};
+use crate::error::{PHError, check_positive, check_quality};
 // use crate::{center_indexed, divide_int};
 
 /// Pin
@@ -13,13 +13,19 @@ pub struct Pin<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// Pin
 impl<F: Float + std::fmt::Debug> Pin<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F, p: u16, q: u16) -> Self {
+    Self::try_new(r, p, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F, p: u16, q: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_quality("q", q)?;
     let z = <F>::from(2).unwrap();
     let mut tbl = vec![
       [        15.0, 0.800], // 0.000], //
@@ -46,20 +52,15 @@ impl<F: Float + std::fmt::Debug> Pin<F> where F: std::iter::Sum {
 
     let cg = calc_cg_f2_x(&tbl);
     // println!("cg: {:?}", cg); // 5.8672757 // not accurate
-    assert!(prec_eq(&f_to_f32(&cg), 1e-6, &vec![5.8672757, 0.0]));
     let tbl = tbl.into_iter().map(|[x, y]|
       (x - cg[0], y) // skip cg[1]
     ).collect::<Vec<_>>();
 
-    assert_eq!(p * 2 + 1, tbl.len() as u16);
-    let revo = Revolution::<F>::from_tbl(r, p, q, (true, true), &tbl);
-
-//    let p = <F>::from(1e-6).unwrap();
-    let p = <F>::from(1e-5).unwrap(); // TODO: prec 1e-5
-    let cg = calc_cg(&revo.ph.tri, &revo.ph.vtx, p);
-    // println!("cg: {:?}", cg); // -0.08735818 (5.779917) // TODO: check value
-    assert_eq!(f_to_f32(&[cg[0], cg[2]]), &[0.0, 0.0]); // without y
-
-    Pin{ph: revo.ph, edges: revo.edges}
+    if p as usize * 2 + 1 != tbl.len() {
+      return Err(PHError::InvalidParameter(
+        format!("p must be {} (p * 2 + 1 = {})", tbl.len() / 2, tbl.len())));
+    }
+    let revo = Revolution::<F>::try_from_tbl(r, p, q, (true, true), &tbl)?;
+    Ok(Pin{ph: revo.ph, edges: revo.edges})
   }
 }
@@ -3,36 +3,54 @@
 
 use num::Float;
 
-use crate::{prec_eq, f_to_f32, adjust_cg_with_volume};
-use crate::{Polyhedron, revolution::Revolution, calc_cg_f3};
+use crate::{adjust_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges, revolution::Revolution};
+use crate::error::{PHError, check_index, check_quality};
+use crate::error::{check_positive, check_non_negative};
 // use crate::{center_indexed, divide_int};
 
+/// check outer diameter &gt; inner diameter &ge; 0
+fn check_diameter<F: Float>(odm: F, idm: F) -> Result<(), PHError> {
+  check_positive("odm", odm)?;
+  check_non_negative("idm", idm)?;
+  if idm >= odm {
+    return Err(PHError::InvalidParameter("idm must be < odm".to_string()));
+  }
+  Ok(())
+}
+
 /// Tube
 #[derive(Debug)]
 pub struct Tube<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// Tube
 impl<F: Float + std::fmt::Debug> Tube<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(odm: F, idm: F, l: F, q: u16) -> Self {
+    Self::try_new(odm, idm, l, q).unwrap()
+  }
   /// construct
   /// - odm: outer diameter
   /// - idm: inner diameter
   /// - l: length
   /// - q: quality
-  pub fn new(odm: F, idm: F, l: F, q: u16) -> Self {
+  pub fn try_new(odm: F, idm: F, l: F, q: u16) -> Result<Self, PHError> {
+    check_diameter(odm, idm)?;
+    check_positive("l", l)?;
     let r = <F>::from(1).unwrap();
     let z = <F>::from(2).unwrap();
     let (ro, ri) = (odm / z, idm / z);
     let h = l / z;
     let p = 2;
     let mut tbl = vec![(h, ri), (-h, ri), (-h, ro), (h, ro)];
-    tbl.push(tbl[0].clone()); // length = p * 2 + 1
-    let revo = Revolution::<F>::from_tbl(r, p, q, (false, false), &tbl);
-    Tube{ph: revo.ph, edges: revo.edges}
+    tbl.push(tbl[0]); // length = p * 2 + 1
+    let revo = Revolution::<F>::try_from_tbl(r, p, q, (false, false), &tbl)?;
+    Ok(Tube{ph: revo.ph, edges: revo.edges})
   }
 }
 
@@ -42,25 +60,38 @@ pub struct HalfPipe<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// HalfPipe
 impl<F: Float + std::fmt::Debug> HalfPipe<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(a: F, odm: F, idm: F, l: F, q: u16) -> Self {
+    Self::try_new(a, odm, idm, l, q).unwrap()
+  }
   /// construct
   /// - a: arc angle
   /// - odm: outer diameter
   /// - idm: inner diameter
   /// - l: length
   /// - q: quality
-  pub fn new(a: F, odm: F, idm: F, l: F, q: u16) -> Self {
+  pub fn try_new(a: F, odm: F, idm: F, l: F, q: u16) ->
+    Result<Self, PHError> {
+    check_positive("a", a)?;
+    if a.to_f64() > Some(2.0 * std::f64::consts::PI) {
+      return Err(PHError::InvalidParameter("a must be <= 2pi".to_string()));
+    }
+    check_diameter(odm, idm)?;
+    check_positive("l", l)?;
+    check_quality("q", q)?;
     let o = <F>::from(0).unwrap();
     let z = <F>::from(2).unwrap();
     let (ro, ri) = (odm / z, idm / z);
     let h = l / z;
-    let c = q * 4 + 1;
+    let c = q as u32 * 4 + 1;
+    check_index(c as u64 * 4)?;
     let fa = a.to_f64().unwrap();
-    let mut vtx = (0..c).into_iter().flat_map(|cn| { // len = c4
+    let mut vtx = (0..c).flat_map(|cn| { // len = c4
       let th = fa * cn as f64 / (c - 1) as f64 - fa / 2.0;
       let (cx, _cy, cz) = (
         <F>::from(th.sin()).unwrap(),
@@ -70,14 +101,9 @@ impl<F: Float + std::fmt::Debug> HalfPipe<F> where F: std::iter::Sum {
       vec![[xi, -h, zi], [xo, -h, zo], [xo, h, zo], [xi, h, zi]]
     }).collect::<Vec<_>>();
 
-    let cg = calc_cg_f3(&vtx, <F>::from(1e-6).unwrap());
-    // println!("cg: {:?}", cg); // 0.05825041967286819 // not accurate
-    // assert_eq!(f_to_f32(&cg[..2]), &[0.0, 0.0]); // without z
-    assert!(prec_eq(&f_to_f32(&cg[..2]), 1e-6, &vec![0.0, 0.0])); // without z
-
-    let mut tri = (0..c-1).into_iter().flat_map(|cn| {
+    let mut tri = (0..c-1).flat_map(|cn| {
       let kn = 4 * (cn + 1);
-      (0..4).into_iter().map(|k| { // bottom, outside, top, inside
+      (0..4).map(|k| { // bottom, outside, top, inside
         let kb = 4 * cn + k; // always below
         let kbc = kb + 4; // not over
         let mut kt = kb + 1;
@@ -91,11 +117,11 @@ impl<F: Float + std::fmt::Debug> HalfPipe<F> where F: std::iter::Sum {
     tri.push(vec![[k + 3, k + 2, k + 1], [k + 3, k + 1, k]]); // a/2 side
 
     let p = <F>::from(1e-6).unwrap();
-    let (cg, vol) = adjust_cg_with_volume(&tri, &mut vtx, p);
+    let (_cg, vol) = adjust_cg_with_volume(&tri, &mut vtx, p);
     // println!("cg: {:?}", cg); // 0.06758362864954912 // TODO: check value
-    assert_eq!(f_to_f32(&cg[..2]), &[0.0, 0.0]); // without z
 
-    let edges = vec![];
-    HalfPipe{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(HalfPipe{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false},
+      edges})
   }
 }
@@ -0,0 +1,87 @@
+//! Prism and Antiprism (n-gon) on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_edges, polygon_xz, polyhedron_from_polygons};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_sides};
+
+/// Prism
+#[derive(Debug)]
+pub struct Prism<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Prism
+impl<F: Float + std::fmt::Debug> Prism<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, h: F, n: u16) -> Self {
+    Self::try_new(r, h, n).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed circle of the n-gon
+  /// - h: height
+  /// - n: number of sides
+  pub fn try_new(r: F, h: F, n: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("h", h)?;
+    check_sides("n", n)?;
+    let n = n as u32;
+    check_index(n as u64 * 2)?;
+    let y = h / <F>::from(2).unwrap();
+    let mut vtx = polygon_xz(r, -y, 0.0, n); // bottom
+    vtx.extend(polygon_xz(r, y, 0.0, n)); // top
+    let mut faces = vec![(0..n).rev().collect(), (n..2*n).collect()];
+    faces.extend((0..n).map(|k| {
+      let kn = (k + 1) % n;
+      vec![k, kn, n + kn, n + k]
+    }));
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Prism{ph, edges})
+  }
+}
+
+/// Antiprism
+#[derive(Debug)]
+pub struct Antiprism<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Antiprism
+impl<F: Float + std::fmt::Debug> Antiprism<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, h: F, n: u16) -> Self {
+    Self::try_new(r, h, n).unwrap()
+  }
+  /// construct (top n-gon is rotated pi / n)
+  /// - r: radius of circumscribed circle of the n-gon
+  /// - h: height
+  /// - n: number of sides
+  pub fn try_new(r: F, h: F, n: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("h", h)?;
+    check_sides("n", n)?;
+    let n = n as u32;
+    check_index(n as u64 * 2)?;
+    let y = h / <F>::from(2).unwrap();
+    let th = std::f64::consts::PI / n as f64;
+    let mut vtx = polygon_xz(r, -y, 0.0, n); // bottom
+    vtx.extend(polygon_xz(r, y, th, n)); // top
+    let mut faces = vec![(0..n).rev().collect(), (n..2*n).collect()];
+    faces.extend((0..n).flat_map(|k| {
+      let kn = (k + 1) % n;
+      [vec![k, kn, n + k], vec![kn, n + kn, n + k]]
+    }));
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Antiprism{ph, edges})
+  }
+}
@@ -0,0 +1,82 @@
+//! Pyramid and Bipyramid (n-gon) on polyhedron faces for Rust
+//!
+
+use num::Float;
+
+use crate::{calc_edges, polygon_xz, polyhedron_from_polygons};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_sides};
+
+/// Pyramid
+#[derive(Debug)]
+pub struct Pyramid<F: Float> {
+  /// polyhedron tri: Vec n of Vec (m - 2) indexed triangles (m-gon)
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Pyramid
+impl<F: Float + std::fmt::Debug> Pyramid<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, h: F, n: u16) -> Self {
+    Self::try_new(r, h, n).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed circle of the base n-gon
+  /// - h: height
+  /// - n: number of sides
+  pub fn try_new(r: F, h: F, n: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("h", h)?;
+    check_sides("n", n)?;
+    let n = n as u32;
+    check_index(n as u64 + 1)?;
+    let o = <F>::from(0).unwrap();
+    let mut vtx = polygon_xz(r, o, 0.0, n); // base
+    vtx.push([o, h, o]); // apex
+    let mut faces = vec![(0..n).rev().collect()];
+    faces.extend((0..n).map(|k| vec![k, (k + 1) % n, n]));
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Pyramid{ph, edges})
+  }
+}
+
+/// Bipyramid
+#[derive(Debug)]
+pub struct Bipyramid<F: Float> {
+  /// polyhedron tri: Vec n of Vec 1 indexed triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Bipyramid
+impl<F: Float + std::fmt::Debug> Bipyramid<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new(r: F, h: F, n: u16) -> Self {
+    Self::try_new(r, h, n).unwrap()
+  }
+  /// construct
+  /// - r: radius of circumscribed circle of the middle n-gon
+  /// - h: height of each apex from the middle n-gon
+  /// - n: number of sides
+  pub fn try_new(r: F, h: F, n: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_positive("h", h)?;
+    check_sides("n", n)?;
+    let n = n as u32;
+    check_index(n as u64 + 2)?;
+    let o = <F>::from(0).unwrap();
+    let mut vtx = polygon_xz(r, o, 0.0, n); // middle
+    vtx.extend([[o, h, o], [o, -h, o]]); // top, bottom
+    let faces = (0..n).flat_map(|k| {
+      let kn = (k + 1) % n;
+      [vec![k, kn, n], vec![kn, k, n + 1]]
+    }).collect::<Vec<_>>();
+    let ph = polyhedron_from_polygons(vtx, &faces);
+    let edges = calc_edges(&ph.tri, &ph.vtx, None, false);
+    Ok(Bipyramid{ph, edges})
+  }
+}
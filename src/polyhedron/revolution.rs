@@ -3,8 +3,11 @@
 
 use num::Float;
 
-use crate::{f_to_f32, adjust_cg_with_volume};
-use crate::Polyhedron;
+use crate::{adjust_cg_with_volume, calc_edges};
+use crate::{uv_indexed, cap_uv};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_quality};
+use crate::error::check_volume;
 // use crate::{center_indexed, divide_int};
 
 /// Revolution
@@ -13,60 +16,95 @@ pub struct Revolution<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// Revolution
 impl<F: Float + std::fmt::Debug> Revolution<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  /// - fo: (bottom, top) false: fixed end, true: open end
+  pub fn new<Func>(r: F, p: u16, q: u16, fo: (bool, bool), f: Func) -> Self
+    where Func: FnMut(u32, u32) -> (F, F) {
+    Self::try_new(r, p, q, fo, f).unwrap()
+  }
   /// construct
   /// - fo: (bottom, top) false: fixed end, true: open end
-  pub fn new<Func>(r: F, p: u16, q: u16, fo: (bool, bool), mut f: Func) -> Self
-    where Func: FnMut(u16, u16) -> (F, F) {
+  pub fn try_new<Func>(r: F, p: u16, q: u16, fo: (bool, bool), mut f: Func) ->
+    Result<Self, PHError> where Func: FnMut(u32, u32) -> (F, F) {
+    check_positive("r", r)?;
+    check_quality("p", p)?;
+    check_quality("q", q)?;
     let o = <F>::from(0).unwrap();
-    let s = p * 2 + 1; // middle = p
-    let c = q * 4;
-    let cs = c * s;
-    let mut vtx = (0..s).into_iter().flat_map(|sn| {
+    let s = p as u32 * 2 + 1; // middle = p
+    let c = q as u32 * 4;
+    let cc = c + 1; // duplex seam (cn = c is cn = 0)
+    let cs = cc * s;
+    let nc = fo.0 as u64 + fo.1 as u64; // caps
+    check_index(cs as u64 + nc * (c as u64 + 1))?;
+    let th = |cn: u32| 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
+    let mut vtx = (0..s).flat_map(|sn| {
       let g = f(sn, s);
-      (0..c).into_iter().map(|cn| {
-        let cth = 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
+      (0..cc).map(|cn| {
         let w = r * g.1;
         [
-          w * <F>::from(cth.sin()).unwrap(),
+          w * <F>::from(th(cn).sin()).unwrap(),
           r * g.0,
-          w * <F>::from(cth.cos()).unwrap()]
+          w * <F>::from(th(cn).cos()).unwrap()]
       }).collect::<Vec<_>>()
     }).collect::<Vec<_>>();
+    let hv = if nc > 0 { 0.5 } else { 1.0 }; // side upper half with caps
+    let mut uvs = (0..s).flat_map(|sn| {
+      (0..cc).map(move |cn| [
+        <F>::from(cn as f64 / c as f64).unwrap(),
+        <F>::from(hv * (1.0 - sn as f64 / (s - 1) as f64)).unwrap()])
+    }).collect::<Vec<_>>();
     let (fo0, mut fo1) = (cs, cs);
     if fo.0 { vtx.push([o, r * f(0, s).0, o]); fo1 += 1; } // [fo0] bottom
     if fo.1 { vtx.push([o, r * f(s - 1, s).0, o]); } // [fo1] top
-    let tri = (0..s-1).into_iter().flat_map(|sn| { // always below
-      (0..c).into_iter().flat_map(|cn| {
-        let k = sn * c + cn; // always below
-        let ks = k + c; // not over
-        let mut ksc = k + c + 1;
-        if ksc >= (sn + 2) * c { ksc -= c; } // not over
-        let mut kc = k + 1;
-        if kc >= (sn + 1) * c { kc -= c; } // not over
-        // println!("[{} {} {} {}]", k, ks, ksc, kc);
+    if fo.0 { uvs.push(cap_uv(0.0, 0.0, false)); }
+    if fo.1 { uvs.push(cap_uv(0.0, 0.0, true)); }
+    let (rb, mut rt) = (vtx.len() as u32, vtx.len() as u32); // cap rims
+    if fo.0 {
+      vtx.extend_from_within(0..c as usize);
+      uvs.extend((0..c).map(|cn| cap_uv(th(cn), 1.0, false)));
+      rt += c;
+    }
+    if fo.1 {
+      let k = ((s - 1) * cc) as usize;
+      vtx.extend_from_within(k..k + c as usize);
+      uvs.extend((0..c).map(|cn| cap_uv(th(cn), 1.0, true)));
+    }
+    let tri = (0..s-1).flat_map(|sn| { // always below
+      (0..c).flat_map(|cn| {
+        let k = sn * cc + cn; // always below
+        let (kc, ks, ksc) = (k + 1, k + cc, k + cc + 1); // not over
+        let ck = (cn + 1) % c;
         let mut v = vec![];
-        if fo.0 && sn == 0 { v.push(vec![[fo0, kc, k]]); } // bottom
+        if fo.0 && sn == 0 { v.push(vec![[fo0, rb + ck, rb + cn]]); } // bottom
         v.push(vec![[k, kc, ksc], [k, ksc, ks]]); // side
-        if fo.1 && sn == s - 2 { v.push(vec![[fo1, ks, ksc]]); } // top
+        if fo.1 && sn == s - 2 { v.push(vec![[fo1, rt + cn, rt + ck]]); } // top
         v
       }).collect::<Vec<_>>()
     }).collect::<Vec<_>>();
+    let uv = uv_indexed(&tri, &uvs);
     let p = <F>::from(1e-6).unwrap();
-    let (cg, vol) = adjust_cg_with_volume(&tri, &mut vtx, p);
-    assert_eq!(f_to_f32(&[cg[0], cg[2]]), &[0.0, 0.0]); // without y
-    let edges = vec![];
-    Revolution{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let (_cg, vol) = adjust_cg_with_volume(&tri, &mut vtx, p);
+    check_volume(vol)?;
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Revolution{ph: Polyhedron{vtx, tri, uv, vol, center: false},
+      edges})
   }
-  /// construct
+  /// construct (panic when try_from_tbl returns Err)
   /// - fo: (bottom, top) false: fixed end, true: open end
-  pub fn from_tbl(r: F, p: u16, q: u16, fo: (bool, bool), tbl: &Vec<(F, F)>) ->
+  pub fn from_tbl(r: F, p: u16, q: u16, fo: (bool, bool), tbl: &[(F, F)]) ->
     Self {
-    Revolution::new(r, p, q, fo, |n: u16, m: u16| {
+    Self::try_from_tbl(r, p, q, fo, tbl).unwrap()
+  }
+  /// construct
+  /// - fo: (bottom, top) false: fixed end, true: open end
+  pub fn try_from_tbl(r: F, p: u16, q: u16, fo: (bool, bool),
+    tbl: &[(F, F)]) -> Result<Self, PHError> {
+    Revolution::try_new(r, p, q, fo, |n: u32, m: u32| {
       tbl[n as usize % m as usize]
     })
   }
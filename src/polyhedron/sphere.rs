@@ -3,8 +3,9 @@
 
 use num::Float;
 
-use crate::calc_cg_with_volume;
-use crate::Polyhedron;
+use crate::{calc_cg_with_volume, calc_edges, uv_indexed};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_index, check_positive, check_quality};
 
 /// RSphere
 #[derive(Debug)]
@@ -12,18 +13,27 @@ pub struct RSphere<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// RSphere
 impl<F: Float + std::fmt::Debug> RSphere<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F, q: u16) -> Self {
+    Self::try_new(r, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F, q: u16) -> Result<Self, PHError> {
+    check_positive("r", r)?;
+    check_quality("q", q)?;
+    let q = q as u32;
     let s = q * 2 + 1;
     let c = q * 4;
-    let vtx = (0..s).into_iter().flat_map(|sn| {
+    let cc = c + 1; // duplex seam (cn = c is cn = 0)
+    check_index(s as u64 * cc as u64)?;
+    let vtx = (0..s).flat_map(|sn| {
       let sth = std::f64::consts::PI * sn as f64 / (s - 1) as f64; // 0 to =pi
-      (0..c).into_iter().map(|cn| {
+      (0..cc).map(|cn| {
         let cth = 2.0 * std::f64::consts::PI * cn as f64 / c as f64;
         let w = r * <F>::from(sth.sin()).unwrap();
         [
@@ -32,20 +42,26 @@ impl<F: Float + std::fmt::Debug> RSphere<F> where F: std::iter::Sum {
           w * <F>::from(cth.cos()).unwrap()]
       }).collect::<Vec<_>>()
     }).collect::<Vec<_>>();
-    let tri = (0..s-1).into_iter().flat_map(|sn| { // always below
-      (0..c).into_iter().map(|cn| {
-        let k = sn * c + cn; // always below
-        let ks = k + c; // not over
-        let mut ksc = k + c + 1;
-        if ksc >= (sn + 2) * c { ksc -= c; } // not over
-        let mut kc = k + 1;
-        if kc >= (sn + 1) * c { kc -= c; } // not over
-        // println!("[{} {} {} {}]", k, kc, ksc, ks);
-        vec![[k, kc, ksc], [k, ksc, ks]]
+    let uvs = (0..s).flat_map(|sn| { // equirectangular
+      (0..cc).map(move |cn| [
+        <F>::from(cn as f64 / c as f64).unwrap(),
+        <F>::from(1.0 - sn as f64 / (s - 1) as f64).unwrap()])
+    }).collect::<Vec<_>>();
+    let tri = (0..s-1).flat_map(|sn| { // always below
+      (0..c).map(|cn| {
+        let k = sn * cc + cn; // always below
+        let (kc, ks, ksc) = (k + 1, k + cc, k + cc + 1); // not over
+        match sn { // skip degenerated triangles on the poles
+        0 => vec![[k, ksc, ks]],
+        _ if sn == s - 2 => vec![[k, kc, ksc]],
+        _ => vec![[k, kc, ksc], [k, ksc, ks]]
+        }
       }).collect::<Vec<_>>()
     }).collect::<Vec<_>>();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    RSphere{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let uv = uv_indexed(&tri, &uvs);
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(RSphere{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
   }
 }
@@ -0,0 +1,257 @@
+//! Sweep on polyhedron faces for Rust
+//!
+//! sweeps the closed 2D profile along the 3D path (polyline or spline)
+//! oriented by the rotation minimising frames (double reflection)
+//!
+
+use num::Float;
+
+use crate::{calc_cg_with_volume, calc_edges, uv_indexed, cap_uv};
+use crate::{Polyhedron, Edges};
+use crate::{sub_f3, cross_f3, dot_f3, norm_f3, unit_f3};
+use crate::triangulate::{polygon_area, triangulate};
+use crate::error::{PHError, check_index, check_volume};
+
+/// frame on the station [tangent, normal, binormal]
+pub type Frame<F> = [[F; 3]; 3];
+
+/// Sweep
+#[derive(Debug)]
+pub struct Sweep<F: Float> {
+  /// polyhedron tri: Vec n of Vec 2 (side) and Vec t (caps) triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// Sweep
+impl<F: Float + std::fmt::Debug> Sweep<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  /// - profile: closed 2D polygon (x: normal, y: binormal of the frame)
+  /// - path: stations (closed path when the last is the first, welded)
+  /// - caps: (start, end) true: closed by the cap (ignored on closed path)
+  /// - f: (scale, twist angle) of the profile at the station (n, m)
+  pub fn new<Func>(profile: &[[F; 2]], path: &[[F; 3]],
+    caps: (bool, bool), f: Func) -> Self
+    where Func: FnMut(u32, u32) -> (F, F) {
+    Self::try_new(profile, path, caps, f).unwrap()
+  }
+  /// construct
+  /// - profile: closed 2D polygon (x: normal, y: binormal of the frame)
+  /// - path: stations (closed path when the last is the first, welded)
+  /// - caps: (start, end) true: closed by the cap (ignored on closed path)
+  /// - f: (scale, twist angle) of the profile at the station (n, m)
+  pub fn try_new<Func>(profile: &[[F; 2]], path: &[[F; 3]],
+    caps: (bool, bool), f: Func) -> Result<Self, PHError>
+    where Func: FnMut(u32, u32) -> (F, F) {
+    let frames = rotation_minimising_frames(path)?;
+    Self::try_from_frames(profile, path, &frames, caps, f)
+  }
+  /// construct with the frames (e.g. Frenet)
+  /// - frames: [tangent, normal, binormal] (unit) on the stations
+  pub fn try_from_frames<Func>(profile: &[[F; 2]], path: &[[F; 3]],
+    frames: &[Frame<F>], caps: (bool, bool), mut f: Func) ->
+    Result<Self, PHError> where Func: FnMut(u32, u32) -> (F, F) {
+    if profile.len() < 3 {
+      return Err(PHError::InvalidParameter(
+        "profile must have >= 3 points".to_string()));
+    }
+    check_path(path)?;
+    if frames.len() != path.len() {
+      return Err(PHError::InvalidParameter(
+        "frames must be on each station of path".to_string()));
+    }
+    let o = <F>::from(0).unwrap();
+    let area = polygon_area(profile);
+    if area == o || !area.is_finite() {
+      return Err(PHError::Degenerate("profile has no area".to_string()));
+    }
+    let mut pf = profile.to_vec();
+    if area < o { pf.reverse(); } // counter clockwise
+    let closed = is_closed(path);
+    let caps = if closed { (false, false) } else { caps };
+    let c = pf.len() as u32;
+    let cc = c + 1; // duplex seam (cn = c is cn = 0)
+    let s = path.len() as u32;
+    let cs = cc * s;
+    let nc = caps.0 as u64 + caps.1 as u64;
+    check_index(cs as u64 + nc * c as u64)?;
+    let mut vtx = (0..s).flat_map(|sn| {
+      let (x, [_, n, b]) = (path[sn as usize], frames[sn as usize]);
+      let (k, a) = f(sn, s);
+      let (sa, ca) = (a.sin() * k, a.cos() * k);
+      (0..cc).map(|cn| {
+        let q = pf[(cn % c) as usize];
+        let (u, v) = (q[0] * ca - q[1] * sa, q[0] * sa + q[1] * ca);
+        [0, 1, 2].map(|j| x[j] + n[j] * u + b[j] * v)
+      }).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    if closed { // exactly the same seam
+      let k = ((s - 1) * cc) as usize;
+      vtx.truncate(k);
+      vtx.extend_from_within(0..cc as usize);
+    }
+    let hv = if nc > 0 { 0.5 } else { 1.0 }; // side upper half with caps
+    let pu = arc(&pf.iter().map(|q| [q[0], q[1], o]).collect::<Vec<_>>(),
+      true);
+    let pv = arc(path, false);
+    let mut uvs = (0..s).flat_map(|sn| {
+      let (pu, pv) = (&pu, &pv);
+      (0..cc).map(move |cn| [
+        <F>::from(pu[cn as usize]).unwrap(),
+        <F>::from(hv * (1.0 - pv[sn as usize])).unwrap()])
+    }).collect::<Vec<_>>();
+    let (cx, cy) = pf.iter().fold((o, o), |(x, y), q| (x + q[0], y + q[1]));
+    let (cx, cy) = (cx / <F>::from(c).unwrap(), cy / <F>::from(c).unwrap());
+    let rc = pf.iter().fold(o, |r, q|
+      r.max((q[0] - cx).hypot(q[1] - cy))).to_f64().unwrap();
+    let (rb, mut rt) = (vtx.len() as u32, vtx.len() as u32); // cap rims
+    for (cap, top) in [(caps.0, false), (caps.1, true)] {
+      if !cap { continue; }
+      let k = if top { ((s - 1) * cc) as usize } else { 0 };
+      vtx.extend_from_within(k..k + c as usize);
+      uvs.extend(pf.iter().map(|q| {
+        let (x, y) = ((q[0] - cx).to_f64().unwrap(),
+          (q[1] - cy).to_f64().unwrap());
+        cap_uv(x.atan2(y), x.hypot(y) / rc, top)
+      }));
+      if !top { rt += c; }
+    }
+    let mut tri = (0..s-1).flat_map(|sn| {
+      (0..c).map(move |cn| {
+        let k = sn * cc + cn;
+        let (kc, ks, ksc) = (k + 1, k + cc, k + cc + 1); // not over
+        vec![[k, kc, ksc], [k, ksc, ks]]
+      })
+    }).collect::<Vec<_>>();
+    let ts = triangulate(&pf, &[]);
+    if caps.0 { // start (seen from behind)
+      tri.push(ts.iter().map(|t| [rb + t[0], rb + t[2], rb + t[1]]).collect());
+    }
+    if caps.1 { tri.push(ts.iter().map(|t| t.map(|i| rt + i)).collect()); }
+    let uv = uv_indexed(&tri, &uvs);
+    let p = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+    if closed || nc == 2 { check_volume(vol)?; }
+    let edges = calc_edges(&tri, &vtx, Some(p), false);
+    Ok(Sweep{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
+  }
+}
+
+/// rotation minimising frames on the stations (double reflection)
+/// closed path (the last is the first) is corrected to the same frame
+pub fn rotation_minimising_frames<F: Float>(path: &[[F; 3]]) ->
+  Result<Vec<Frame<F>>, PHError> {
+  check_path(path)?;
+  let closed = is_closed(path);
+  let n = path.len();
+  let m = if closed { n - 1 } else { n }; // distinct stations
+  let d = (0..n - 1).map(|i| unit_f3(&sub_f3(&path[i + 1], &path[i])))
+    .collect::<Vec<_>>();
+  let ts = (0..n).map(|i| {
+    let (p, q) = match (i, closed) {
+    (0, false) => (d[0], d[0]),
+    (i, false) if i == n - 1 => (d[i - 1], d[i - 1]),
+    (0, true) => (d[m - 1], d[0]),
+    (i, _) => (d[i - 1], d[i % m])
+    };
+    let t = [0, 1, 2].map(|j| p[j] + q[j]);
+    if dot_f3(&t, &t) > <F>::from(1e-12).unwrap() { unit_f3(&t) } else { q }
+  }).collect::<Vec<_>>();
+  let t0 = ts[0];
+  let k = (0..3).min_by(|&i, &j| t0[i].abs().partial_cmp(&t0[j].abs())
+    .unwrap_or(std::cmp::Ordering::Equal)).unwrap();
+  let mut e = [<F>::from(0).unwrap(); 3];
+  e[k] = <F>::from(1).unwrap();
+  let mut rs = vec![unit_f3(&cross_f3(&cross_f3(&t0, &e), &t0))];
+  for i in 0..n - 1 {
+    let v1 = sub_f3(&path[i + 1], &path[i]);
+    let rl = reflect(&rs[i], &v1);
+    let tl = reflect(&ts[i], &v1);
+    let v2 = sub_f3(&ts[i + 1], &tl);
+    rs.push(unit_f3(&reflect(&rl, &v2)));
+  }
+  if closed { // distribute the angle from the last to the first
+    let (r0, rn) = (rs[0], rs[n - 1]);
+    let a = dot_f3(&cross_f3(&rn, &r0), &t0).atan2(dot_f3(&rn, &r0));
+    let l = arc(path, false);
+    for i in 1..n {
+      let th = a * <F>::from(l[i]).unwrap();
+      let b = cross_f3(&ts[i], &rs[i]);
+      rs[i] = unit_f3(&[0, 1, 2].map(|j|
+        rs[i][j] * th.cos() + b[j] * th.sin()));
+    }
+    rs[n - 1] = r0;
+  }
+  Ok((0..n).map(|i| [ts[i], rs[i], cross_f3(&ts[i], &rs[i])]).collect())
+}
+
+/// points on the Catmull-Rom spline through the points
+/// - q: segments between the points
+/// - closed: the spline returns to the first (the last is the first)
+pub fn catmull_rom<F: Float>(pts: &[[F; 3]], q: u16, closed: bool) ->
+  Vec<[F; 3]> {
+  let n = pts.len();
+  if n < 2 || q == 0 { return pts.to_vec(); }
+  let spans = if closed { n } else { n - 1 };
+  let at = |i: isize| match closed {
+  true => pts[i.rem_euclid(n as isize) as usize],
+  false => pts[i.clamp(0, n as isize - 1) as usize]
+  };
+  let f = |x: f64| <F>::from(x).unwrap();
+  let mut r = (0..spans).flat_map(|i| {
+    let [p0, p1, p2, p3] = [-1, 0, 1, 2].map(|k| at(i as isize + k));
+    (0..q).map(move |k| {
+      let t = f(k as f64 / q as f64);
+      let (t2, t3) = (t * t, t * t * t);
+      [0, 1, 2].map(|j| f(0.5) * (f(2.0) * p1[j] + (p2[j] - p0[j]) * t
+        + (f(2.0) * p0[j] - f(5.0) * p1[j] + f(4.0) * p2[j] - p3[j]) * t2
+        + (f(3.0) * (p1[j] - p2[j]) + p3[j] - p0[j]) * t3))
+    }).collect::<Vec<_>>()
+  }).collect::<Vec<_>>();
+  r.push(if closed { pts[0] } else { pts[n - 1] });
+  r
+}
+
+/// check the path (2 or more stations without zero length segment)
+fn check_path<F: Float>(path: &[[F; 3]]) -> Result<(), PHError> {
+  if path.len() < 2 {
+    return Err(PHError::InvalidParameter(
+      "path must have >= 2 points".to_string()));
+  }
+  let o = <F>::from(0).unwrap();
+  if path.windows(2).any(|w| {
+    let l = norm_f3(&sub_f3(&w[1], &w[0]));
+    l == o || !l.is_finite()
+  }) {
+    return Err(PHError::Degenerate(
+      "path has zero length segment".to_string()));
+  }
+  Ok(())
+}
+
+/// closed path (the last is the first)
+fn is_closed<F: Float>(path: &[[F; 3]]) -> bool {
+  let d = norm_f3(&sub_f3(&path[path.len() - 1], &path[0]));
+  path.len() > 3 && d <= <F>::from(1e-6).unwrap()
+}
+
+/// normalized arc length on the points (closed: back to the first)
+fn arc<F: Float>(ps: &[[F; 3]], closed: bool) -> Vec<f64> {
+  let n = ps.len();
+  let mut l = vec![0.0];
+  for i in 1..n + closed as usize {
+    let d = norm_f3(&sub_f3(&ps[i % n], &ps[i - 1])).to_f64().unwrap();
+    l.push(l[i - 1] + d);
+  }
+  let t = l[l.len() - 1];
+  l.iter().map(|&x| if t > 0.0 { x / t } else { 0.0 }).collect()
+}
+
+/// reflect v by the plane of the normal n
+fn reflect<F: Float>(v: &[F; 3], n: &[F; 3]) -> [F; 3] {
+  let c = dot_f3(n, n);
+  if c == <F>::from(0).unwrap() { return *v; }
+  let k = <F>::from(2).unwrap() * dot_f3(n, v) / c;
+  [0, 1, 2].map(|j| v[j] - n[j] * k)
+}
@@ -3,8 +3,9 @@
 
 use num::Float;
 
-use crate::{prec_eq, f_to_f32, calc_cg_with_volume};
-use crate::{Polyhedron, calc_cg_f3};
+use crate::{calc_cg_with_volume, calc_edges};
+use crate::{Polyhedron, Edges};
+use crate::error::{PHError, check_positive};
 
 /// Tetra
 #[derive(Debug)]
@@ -12,13 +13,18 @@ pub struct Tetra<F: Float> {
   /// polyhedron tri: Vec 4 of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 3])>
+  pub edges: Edges
 }
 
 /// Tetra
 impl<F: Float + std::fmt::Debug> Tetra<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(r: F) -> Self {
+    Self::try_new(r).unwrap()
+  }
+  /// construct
+  pub fn try_new(r: F) -> Result<Self, PHError> {
+    check_positive("r", r)?;
     let r2 = 2.0f64.sqrt();
     let r6 = 3.0f64.sqrt() * r2; // (not equal to 6.0f64.sqrt() prec 1e-16)
     let o = 0.0f64;
@@ -36,20 +42,15 @@ impl<F: Float + std::fmt::Debug> Tetra<F> where F: std::iter::Sum {
       v.into_iter().map(|f|
         r * <F>::from(f).unwrap()
       ).collect::<Vec<_>>().try_into().unwrap()
-    ).collect();
-    let cg = calc_cg_f3(&vtx, <F>::from(1e-6).unwrap()); // not accurate
-    // println!("cg: {:?}", cg);
-    assert!(prec_eq(&f_to_f32(&cg), 1e-6, &vec![0.0, 0.0, 0.0]));
+    ).collect::<Vec<_>>();
     let tri = vec![
       vec![[3, 1, 0]],
       vec![[3, 2, 1]],
       vec![[3, 0, 2]],
       vec![[2, 0, 1]]
     ];
-    let (cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    // println!("cg: {:?}", cg);
-    assert_eq!(f_to_f32(&cg), &[0.0, 0.0, 0.0]);
-    let edges = vec![];
-    Tetra{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
+    let edges = calc_edges(&tri, &vtx, None, false);
+    Ok(Tetra{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges})
   }
 }
@@ -5,8 +5,11 @@ use num::Float;
 use qm::q::{TQuaternion, Quaternion};
 use qm::v::{TVector, v4::Vector4, v3::Vector3};
 
-use crate::calc_cg_with_volume;
-use crate::{Polyhedron, revolution::Revolution};
+use crate::{calc_cg_with_volume, calc_edges, uv_indexed};
+use crate::{sub_f3, cross_f3, dot_f3, norm_f3, unit_f3};
+use crate::{Polyhedron, Edges, revolution::Revolution};
+use crate::sweep::{Sweep, Frame, rotation_minimising_frames};
+use crate::error::{PHError, check_index, check_positive, check_quality};
 
 /// Torus
 #[derive(Debug)]
@@ -14,16 +17,29 @@ pub struct Torus<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// Torus
 impl<F: Float + std::fmt::Debug> Torus<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(c: F, r: F, p: u16, q: u16) -> Self {
-    let p = p * 4;
-    let q = q * 4;
-    let vtx = (0..p).into_iter().flat_map(|pn| {
+    Self::try_new(c, r, p, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(c: F, r: F, p: u16, q: u16) -> Result<Self, PHError> {
+    check_positive("c", c)?;
+    check_positive("r", r)?;
+    if r >= c {
+      return Err(PHError::InvalidParameter("r must be < c".to_string()));
+    }
+    check_quality("p", p)?;
+    check_quality("q", q)?;
+    let p = p as u32 * 4;
+    let q = q as u32 * 4;
+    let qq = q + 1; // duplex seam (qn = q is qn = 0, pn = p is pn = 0)
+    check_index((p + 1) as u64 * qq as u64)?;
+    let vtx = (0..=p).flat_map(|pn| {
       let o = <F>::from(0).unwrap();
       let l = <F>::from(1).unwrap();
       let pi_r = std::f64::consts::PI / 2.0;
@@ -32,7 +48,7 @@ impl<F: Float + std::fmt::Debug> Torus<F> where F: std::iter::Sum {
         c * <F>::from(pth.sin()).unwrap(),
         o,
         c * <F>::from(pth.cos()).unwrap());
-      (0..q).into_iter().map(|qn| {
+      (0..qq).map(|qn| {
         let qth = 2.0 * std::f64::consts::PI * qn as f64 / q as f64;
         let v = Vector4::<F>::new(&vec![
           r * <F>::from(qth.cos()).unwrap(),
@@ -45,19 +61,26 @@ impl<F: Float + std::fmt::Debug> Torus<F> where F: std::iter::Sum {
         [cx + d[0], cy + d[1], cz + d[2]]
       }).collect::<Vec<_>>()
     }).collect::<Vec<_>>();
-    let e = p * q;
-    let tri = (0..p).into_iter().flat_map(|pn| {
-      (0..q).into_iter().map(|qn| {
-        let k = pn * q + qn;
-        let kp = (k + q) % e;
-        let kpq = (k + q + 1) % e;
-        let kq = (k + 1) % e;
+    let uvs = (0..=p).flat_map(|pn| {
+      (0..qq).map(move |qn| [
+        <F>::from(pn as f64 / p as f64).unwrap(),
+        <F>::from(qn as f64 / q as f64).unwrap()])
+    }).collect::<Vec<_>>();
+    let tri = (0..p).flat_map(|pn| {
+      (0..q).map(|qn| {
+        let k = pn * qq + qn;
+        let (kp, kpq, kq) = (k + qq, k + qq + 1, k + 1); // not over
         vec![[k, kq, kpq], [k, kpq, kp]]
       }).collect::<Vec<_>>()
     }).collect::<Vec<_>>();
-    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, <F>::from(1e-6).unwrap());
-    let edges = vec![];
-    Torus{ph: Polyhedron{vtx, tri, uv: vec![], vol, center: false}, edges}
+    let uv = uv_indexed(&tri, &uvs);
+    let e = <F>::from(1e-6).unwrap();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, e);
+    let seam = |k: u32| (k / qq % p) * qq + k % qq % q; // first on the seam
+    let tri_s = tri.iter().map(|f|
+      f.iter().map(|t| t.map(seam)).collect()).collect::<Vec<_>>();
+    let edges = calc_edges(&tri_s, &vtx, None, false);
+    Ok(Torus{ph: Polyhedron{vtx, tri, uv, vol, center: false}, edges})
   }
 }
 
@@ -67,21 +90,30 @@ pub struct RTorus<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// RTorus
 impl<F: Float + std::fmt::Debug> RTorus<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(c: F, r: F, p: u16, q: u16) -> Self {
+    Self::try_new(c, r, p, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(c: F, r: F, p: u16, q: u16) -> Result<Self, PHError> {
+    check_positive("c", c)?;
+    check_positive("r", r)?;
+    if r >= c {
+      return Err(PHError::InvalidParameter("r must be < c".to_string()));
+    }
     let l = <F>::from(1).unwrap();
-    let revo = Revolution::<F>::new(l, p, q, (false, false),
-      |n: u16, m: u16| -> (F, F) {
+    let revo = Revolution::<F>::try_new(l, p, q, (false, false),
+      |n: u32, m: u32| -> (F, F) {
       let k = if n == m - 1 { 0.0 } else { n as f64 / (m - 1) as f64 };
       let th = 2.0 * std::f64::consts::PI * k;
       (r * <F>::from(th.sin()).unwrap(), c + r * <F>::from(th.cos()).unwrap())
-    });
-    RTorus{ph: revo.ph, edges: revo.edges}
+    })?;
+    Ok(RTorus{ph: revo.ph, edges: revo.edges})
   }
 }
 
@@ -91,20 +123,155 @@ pub struct Ring<F: Float> {
   /// polyhedron tri: Vec n of Vec 1 indexed triangles
   pub ph: Polyhedron<F>,
   /// edges (duplex)
-  pub edges: Vec<(u16, [u16; 8])>
+  pub edges: Edges
 }
 
 /// Ring
 impl<F: Float + std::fmt::Debug> Ring<F> where F: std::iter::Sum {
-  /// construct
+  /// construct (panic when try_new returns Err)
   pub fn new(c: F, d: F, e: F, p: u16, q: u16) -> Self {
+    Self::try_new(c, d, e, p, q).unwrap()
+  }
+  /// construct
+  pub fn try_new(c: F, d: F, e: F, p: u16, q: u16) -> Result<Self, PHError> {
+    check_positive("c", c)?;
+    check_positive("d", d)?;
+    check_positive("e", e)?;
     let l = <F>::from(1).unwrap();
-    let revo = Revolution::<F>::new(l, p, q, (false, false),
-      |n: u16, m: u16| -> (F, F) {
+    let revo = Revolution::<F>::try_new(l, p, q, (false, false),
+      |n: u32, m: u32| -> (F, F) {
       let k = if n == m - 1 { 0.0 } else { n as f64 / (m - 1) as f64 };
       let th = 2.0 * std::f64::consts::PI * k;
       (d * <F>::from(th.sin()).unwrap(), c + e * <F>::from(th.cos()).unwrap())
-    });
-    Ring{ph: revo.ph, edges: revo.edges}
+    })?;
+    Ok(Ring{ph: revo.ph, edges: revo.edges})
+  }
+}
+
+/// FrameMode (orientation of the circle along the curve)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameMode {
+  /// Frenet (normal to the center of curvature, no straight part)
+  Frenet,
+  /// parallel transport (rotation minimising)
+  ParallelTransport
+}
+
+/// Curve (t: 0 to 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve<F: Float> {
+  /// (p, q) torus knot on the torus (c, r) around Y axis
+  TorusKnot(u16, u16, F, F),
+  /// trefoil (scale)
+  Trefoil(F),
+  /// helix (radius, pitch, turns) along Y axis (open)
+  Helix(F, F, F)
+}
+
+/// Curve
+impl<F: Float> Curve<F> {
+  /// closed curve
+  pub fn is_closed(&self) -> bool {
+    !matches!(self, Curve::Helix(..))
   }
+  /// point on the curve at t
+  pub fn at(&self, t: F) -> [F; 3] {
+    let th = <F>::from(2.0 * std::f64::consts::PI).unwrap() * t;
+    let f = |x: f64| <F>::from(x).unwrap();
+    match *self {
+    Curve::TorusKnot(p, q, c, r) => {
+      let (a, b) = (th * f(p as f64), th * f(q as f64));
+      let w = c + r * b.cos();
+      [w * a.sin(), r * b.sin(), w * a.cos()]
+    },
+    Curve::Trefoil(s) => [
+      s * (th.sin() + f(2.0) * (th * f(2.0)).sin()),
+      -s * (th * f(3.0)).sin(),
+      s * (th.cos() - f(2.0) * (th * f(2.0)).cos())],
+    Curve::Helix(r, h, n) => {
+      let a = th * n;
+      [r * a.sin(), h * n * t, r * a.cos()]
+    }
+    }
+  }
+}
+
+/// CurveTube
+#[derive(Debug)]
+pub struct CurveTube<F: Float> {
+  /// polyhedron tri: Vec n of Vec 2 (side) and Vec t (caps) triangles
+  pub ph: Polyhedron<F>,
+  /// edges (duplex)
+  pub edges: Edges
+}
+
+/// CurveTube
+impl<F: Float + std::fmt::Debug> CurveTube<F> where F: std::iter::Sum {
+  /// construct (panic when try_new returns Err)
+  pub fn new<Func>(r: F, p: u16, q: u16, closed: bool, fm: FrameMode,
+    c: Func) -> Self where Func: FnMut(F) -> [F; 3] {
+    Self::try_new(r, p, q, closed, fm, c).unwrap()
+  }
+  /// construct (uv: around, along the tube)
+  /// - r: radius of the tube
+  /// - p: quality along the curve (p * 4 segments)
+  /// - q: quality around the tube (q * 4 segments)
+  /// - closed: true: c(1) is c(0), false: open ends with caps
+  /// - fm: frames
+  /// - c: curve c(t) (t: 0 to 1, evaluated a step out of the open ends)
+  pub fn try_new<Func>(r: F, p: u16, q: u16, closed: bool, fm: FrameMode,
+    mut c: Func) -> Result<Self, PHError> where Func: FnMut(F) -> [F; 3] {
+    check_positive("r", r)?;
+    check_quality("p", p)?;
+    check_quality("q", q)?;
+    let (p, q) = (p as u32 * 4, q as u32 * 4);
+    let t = |k: i64| <F>::from(k as f64 / p as f64).unwrap();
+    let mut ps = (-1..=p as i64 + 1).map(|k| c(t(k))).collect::<Vec<_>>();
+    if closed { // exactly the same seam
+      ps[p as usize + 1] = ps[1];
+      (ps[0], ps[p as usize + 2]) = (ps[p as usize], ps[2]);
+    }
+    let path = ps[1..=p as usize + 1].to_vec();
+    let frames = match fm {
+    FrameMode::Frenet => frenet(&ps)?,
+    FrameMode::ParallelTransport => rotation_minimising_frames(&path)?
+    };
+    let profile = (0..q).map(|k| {
+      let th = 2.0 * std::f64::consts::PI * k as f64 / q as f64;
+      [r * <F>::from(th.cos()).unwrap(), r * <F>::from(th.sin()).unwrap()]
+    }).collect::<Vec<_>>();
+    let l = <F>::from(1).unwrap();
+    let o = <F>::from(0).unwrap();
+    let tube = Sweep::try_from_frames(&profile, &path, &frames,
+      (!closed, !closed), |_, _| (l, o))?;
+    Ok(CurveTube{ph: tube.ph, edges: tube.edges})
+  }
+  /// construct from the built in curve (panic when try_from_curve Err)
+  pub fn from_curve(curve: Curve<F>, r: F, p: u16, q: u16, fm: FrameMode) ->
+    Self {
+    Self::try_from_curve(curve, r, p, q, fm).unwrap()
+  }
+  /// construct from the built in curve
+  pub fn try_from_curve(curve: Curve<F>, r: F, p: u16, q: u16,
+    fm: FrameMode) -> Result<Self, PHError> {
+    Self::try_new(r, p, q, curve.is_closed(), fm, |t| curve.at(t))
+  }
+}
+
+/// Frenet frames on the points (without the first and the last)
+fn frenet<F: Float>(ps: &[[F; 3]]) -> Result<Vec<Frame<F>>, PHError> {
+  let e = <F>::from(1e-12).unwrap();
+  ps.windows(3).map(|w| {
+    let d = sub_f3(&w[2], &w[0]);
+    let a = [0, 1, 2].map(|j| w[2][j] - w[1][j] - w[1][j] + w[0][j]);
+    let t = unit_f3(&d);
+    let k = dot_f3(&a, &t);
+    let n = [0, 1, 2].map(|j| a[j] - t[j] * k);
+    if norm_f3(&n) <= e * dot_f3(&d, &d) {
+      return Err(PHError::Degenerate(
+        "curvature is 0 (Frenet frame is not defined)".to_string()));
+    }
+    let n = unit_f3(&n);
+    Ok([t, n, cross_f3(&t, &n)])
+  }).collect()
 }
@@ -0,0 +1,341 @@
+//! Query (ray cast, winding number, closest point) on polyhedron faces
+//!
+//! triangles are addressed by (fi, ti) the same as FTVI
+//! every query runs on all triangles or on the Bvh when given
+//!
+
+use num::Float;
+
+use crate::{sub_f3, cross_f3, dot_f3, norm_f3};
+
+/// Hit
+#[derive(Debug, Clone)]
+pub struct Hit<F: Float> {
+  /// face index (same as FTVI fi)
+  pub fi: usize,
+  /// triangle index (same as FTVI ti)
+  pub ti: usize,
+  /// ray parameter (distance from the point on closest_point)
+  pub t: F,
+  /// point on the triangle
+  pub p: [F; 3],
+  /// barycentrics of the vertices of the triangle (vi 0 1 2)
+  pub bary: [F; 3],
+  /// interpolated uv (None when uv is empty)
+  pub uv: Option<[F; 2]>,
+  /// true when hits (or is on) the front side of the triangle
+  pub front: bool
+}
+
+/// Node of Bvh
+#[derive(Debug, Clone)]
+pub struct Node<F: Float> {
+  /// min of the bounding box
+  pub min: [F; 3],
+  /// max of the bounding box
+  pub max: [F; 3],
+  /// children (None when leaf)
+  pub children: Option<[usize; 2]>,
+  /// range of Bvh tris under this node
+  pub range: (usize, usize),
+  /// sum of area vectors (for the far field of the winding number)
+  pub n: [F; 3],
+  /// area weighted center
+  pub c: [F; 3],
+  /// radius of the bounding box around c
+  pub r: F
+}
+
+/// Bvh bounding volume hierarchy on the triangles
+#[derive(Debug, Clone)]
+pub struct Bvh<F: Float> {
+  /// nodes (0: root)
+  pub nodes: Vec<Node<F>>,
+  /// triangles (fi, ti) ordered by the leaves
+  pub tris: Vec<(usize, usize)>
+}
+
+/// max triangles in a leaf
+const LEAF: usize = 4;
+
+/// Bvh
+impl<F: Float> Bvh<F> {
+  /// construct (median split on the longest axis of the centers)
+  /// - idx: index of triangles on each faces
+  /// - vtx: vertices
+  pub fn new(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]]) -> Self {
+    let mut tris = idx.iter().enumerate().flat_map(|(fi, f)|
+      (0..f.len()).map(move |ti| (fi, ti))).collect::<Vec<_>>();
+    let mut nodes = vec![];
+    if !tris.is_empty() {
+      let n = tris.len();
+      build(&mut nodes, &mut tris, 0, n, idx, vtx);
+    }
+    Bvh{nodes, tris}
+  }
+
+  /// construct from the polyhedron
+  pub fn from_polyhedron<T: crate::polyhedron::TUV<F> + ?Sized>(ph: &T) ->
+    Self {
+    Self::new(ph.ref_tri(), ph.ref_vtx())
+  }
+}
+
+/// build the node on tris[s..e] (returns the node index)
+fn build<F: Float>(nodes: &mut Vec<Node<F>>, tris: &mut Vec<(usize, usize)>,
+  s: usize, e: usize, idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]]) -> usize {
+  let o = <F>::from(0).unwrap();
+  let vs = |&(fi, ti): &(usize, usize)| idx[fi][ti].map(|i| vtx[i as usize]);
+  let (mut min, mut max) = ([F::infinity(); 3], [F::neg_infinity(); 3]);
+  let (mut cmin, mut cmax) = ([F::infinity(); 3], [F::neg_infinity(); 3]);
+  let (mut n, mut ca, mut a) = ([o; 3], [o; 3], o);
+  for t in tris[s..e].iter() {
+    let v = vs(t);
+    let c = centroid(&v);
+    for j in 0..3 {
+      for p in v.iter() {
+        min[j] = min[j].min(p[j]);
+        max[j] = max[j].max(p[j]);
+      }
+      cmin[j] = cmin[j].min(c[j]);
+      cmax[j] = cmax[j].max(c[j]);
+    }
+    let an = cross_f3(&sub_f3(&v[1], &v[0]), &sub_f3(&v[2], &v[0]));
+    let s = dot_f3(&an, &an).sqrt();
+    for j in 0..3 { n[j] = n[j] + an[j]; ca[j] = ca[j] + c[j] * s; }
+    a = a + s;
+  }
+  let h = <F>::from(2).unwrap();
+  let c = match a > o {
+  true => ca.map(|v| v / a),
+  false => [0, 1, 2].map(|j| (min[j] + max[j]) / h)
+  };
+  let d = [0, 1, 2].map(|j| (c[j] - min[j]).max(max[j] - c[j]));
+  let k = nodes.len();
+  nodes.push(Node{min, max, children: None, range: (s, e),
+    n: n.map(|v| v / h), c, r: dot_f3(&d, &d).sqrt()});
+  if e - s > LEAF {
+    let w = [0, 1, 2].map(|j| cmax[j] - cmin[j]);
+    let ax = if w[0] >= w[1] && w[0] >= w[2] { 0 }
+      else if w[1] >= w[2] { 1 } else { 2 };
+    let m = (s + e) / 2;
+    tris[s..e].select_nth_unstable_by(m - s, |p, q| {
+      let (cp, cq) = (centroid(&vs(p))[ax], centroid(&vs(q))[ax]);
+      cp.partial_cmp(&cq).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let l = build(nodes, tris, s, m, idx, vtx);
+    let r = build(nodes, tris, m, e, idx, vtx);
+    nodes[k].children = Some([l, r]);
+  }
+  k
+}
+
+/// triangles on the Bvh (all triangles when None) visited by the filter
+/// - s: state shared by go and v
+/// - go: false skips the node
+/// - v: visit the triangle (fi, ti)
+fn visit<F: Float, S, G, V>(idx: &[Vec<[u32; 3]>], bvh: Option<&Bvh<F>>,
+  s: &mut S, go: G, v: V) where
+  G: Fn(&mut S, &Node<F>) -> bool, V: Fn(&mut S, usize, usize) {
+  let Some(bvh) = bvh else {
+    for (fi, f) in idx.iter().enumerate() {
+      for ti in 0..f.len() { v(s, fi, ti); }
+    }
+    return;
+  };
+  if bvh.nodes.is_empty() { return; }
+  let mut stack = vec![0];
+  while let Some(k) = stack.pop() {
+    let nd = &bvh.nodes[k];
+    if !go(s, nd) { continue; }
+    match nd.children {
+    Some([l, r]) => { stack.push(r); stack.push(l); },
+    None => for &(fi, ti) in bvh.tris[nd.range.0..nd.range.1].iter() {
+      v(s, fi, ti);
+    }
+    }
+  }
+}
+
+/// nearest so far (t or squared distance, Some((fi, ti, bary, front)))
+type Best<F> = (F, Option<(usize, usize, [F; 3], bool)>);
+
+/// hit on the triangle
+fn hit<F: Float>(uv: &[Vec<[[F; 2]; 3]>], fi: usize, ti: usize, t: F,
+  v: &[[F; 3]; 3], bary: [F; 3], front: bool) -> Hit<F> {
+  let p = [0, 1, 2].map(|j|
+    v[0][j] * bary[0] + v[1][j] * bary[1] + v[2][j] * bary[2]);
+  let uv = match !uv.is_empty() {
+  true => {
+    let w = &uv[fi][ti];
+    Some([0, 1].map(|j|
+      w[0][j] * bary[0] + w[1][j] * bary[1] + w[2][j] * bary[2]))
+  },
+  false => None
+  };
+  Hit{fi, ti, t, p, bary, uv, front}
+}
+
+/// ray cast (the nearest hit on the both sides of the triangles)
+/// - idx: index of triangles on each faces
+/// - uv: uv of triangles on each faces (may be empty)
+/// - vtx: vertices
+/// - bvh: Some(&Bvh) built from the same idx and vtx
+/// - o: origin of the ray
+/// - d: direction of the ray (t is the distance when normalized)
+/// - tmax: hits with 0 &lt;= t &lt; tmax
+pub fn ray_cast<F: Float>(idx: &[Vec<[u32; 3]>],
+  uv: &[Vec<[[F; 2]; 3]>], vtx: &[[F; 3]], bvh: Option<&Bvh<F>>,
+  o: &[F; 3], d: &[F; 3], tmax: F) -> Option<Hit<F>> {
+  let z = <F>::from(0).unwrap();
+  let l = <F>::from(1).unwrap();
+  let inv = d.map(|v| l / v);
+  let mut best: Best<F> = (tmax, None);
+  visit(idx, bvh, &mut best, |(tb, _), nd| {
+    let (mut t0, mut t1) = (z, *tb);
+    for j in 0..3 {
+      let (a, b) = ((nd.min[j] - o[j]) * inv[j], (nd.max[j] - o[j]) * inv[j]);
+      t0 = t0.max(a.min(b));
+      t1 = t1.min(a.max(b));
+    }
+    t0 <= t1
+  }, |(tb, b), fi, ti| {
+    let v = idx[fi][ti].map(|i| vtx[i as usize]);
+    let (e1, e2) = (sub_f3(&v[1], &v[0]), sub_f3(&v[2], &v[0]));
+    let p = cross_f3(d, &e2);
+    let det = dot_f3(&e1, &p);
+    if det == z { return; } // parallel or degenerated
+    let s = sub_f3(o, &v[0]);
+    let u = dot_f3(&s, &p) / det;
+    if u < z || u > l { return; }
+    let q = cross_f3(&s, &e1);
+    let w = dot_f3(d, &q) / det;
+    if w < z || u + w > l { return; }
+    let t = dot_f3(&e2, &q) / det;
+    if t < z || t >= *tb { return; }
+    *tb = t;
+    *b = Some((fi, ti, [l - u - w, u, w], det > z));
+  });
+  let (t, b) = best;
+  b.map(|(fi, ti, bary, front)| {
+    let v = idx[fi][ti].map(|i| vtx[i as usize]);
+    hit(uv, fi, ti, t, &v, bary, front)
+  })
+}
+
+/// generalized winding number (about 1 inside 0 outside of closed mesh)
+/// (far nodes of the Bvh are approximated by the dipole)
+/// - idx: index of triangles on each faces
+/// - vtx: vertices (closed and counter clockwise from outside)
+/// - bvh: Some(&Bvh) built from the same idx and vtx
+/// - q: the point
+pub fn winding_number<F: Float>(idx: &[Vec<[u32; 3]>],
+  vtx: &[[F; 3]], bvh: Option<&Bvh<F>>, q: &[F; 3]) -> F {
+  let beta = <F>::from(2).unwrap();
+  let mut w = <F>::from(0).unwrap();
+  visit(idx, bvh, &mut w, |w, nd| {
+    let c = sub_f3(&nd.c, q);
+    let dd = dot_f3(&c, &c);
+    if nd.children.is_some() && dd.sqrt() > beta * nd.r {
+      *w = *w + dot_f3(&c, &nd.n) / (dd * dd.sqrt()); // solid angle of dipole
+      return false;
+    }
+    true
+  }, |w, fi, ti| {
+    let [a, b, c] = idx[fi][ti].map(|i| sub_f3(&vtx[i as usize], q));
+    let (la, lb, lc) = (norm_f3(&a), norm_f3(&b), norm_f3(&c));
+    let num = dot_f3(&a, &cross_f3(&b, &c));
+    let den = la * lb * lc + dot_f3(&a, &b) * lc + dot_f3(&a, &c) * lb
+      + dot_f3(&b, &c) * la;
+    *w = *w + <F>::from(2).unwrap() * num.atan2(den); // solid angle
+  });
+  w / <F>::from(4.0 * std::f64::consts::PI).unwrap()
+}
+
+/// point containment (winding number &gt; 0.5)
+/// - idx: index of triangles on each faces
+/// - vtx: vertices (closed and counter clockwise from outside)
+/// - bvh: Some(&Bvh) built from the same idx and vtx
+/// - q: the point
+pub fn contains<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]],
+  bvh: Option<&Bvh<F>>, q: &[F; 3]) -> bool {
+  winding_number(idx, vtx, bvh, q) > <F>::from(0.5).unwrap()
+}
+
+/// closest point on the surface (t is the distance)
+/// - idx: index of triangles on each faces
+/// - uv: uv of triangles on each faces (may be empty)
+/// - vtx: vertices
+/// - bvh: Some(&Bvh) built from the same idx and vtx
+/// - q: the point
+pub fn closest_point<F: Float>(idx: &[Vec<[u32; 3]>],
+  uv: &[Vec<[[F; 2]; 3]>], vtx: &[[F; 3]], bvh: Option<&Bvh<F>>,
+  q: &[F; 3]) -> Option<Hit<F>> {
+  let z = <F>::from(0).unwrap();
+  let mut best: Best<F> = (F::infinity(), None);
+  visit(idx, bvh, &mut best, |(db, _), nd| {
+    let d = [0, 1, 2].map(|j|
+      (nd.min[j] - q[j]).max(z).max(q[j] - nd.max[j]));
+    dot_f3(&d, &d) <= *db
+  }, |(db, b), fi, ti| {
+    let v = idx[fi][ti].map(|i| vtx[i as usize]);
+    let bary = closest_bary(&v, q);
+    let p = [0, 1, 2].map(|j|
+      v[0][j] * bary[0] + v[1][j] * bary[1] + v[2][j] * bary[2]);
+    let d = sub_f3(&p, q);
+    let dd = dot_f3(&d, &d);
+    if dd < *db {
+      *db = dd;
+      *b = Some((fi, ti, bary, false));
+    }
+  });
+  let (dd, b) = best;
+  b.map(|(fi, ti, bary, _)| {
+    let v = idx[fi][ti].map(|i| vtx[i as usize]);
+    let n = cross_f3(&sub_f3(&v[1], &v[0]), &sub_f3(&v[2], &v[0]));
+    let h = hit(uv, fi, ti, dd.sqrt(), &v, bary, false);
+    let front = dot_f3(&sub_f3(q, &h.p), &n) >= z;
+    Hit{front, ..h}
+  })
+}
+
+/// barycentrics of the closest point on the triangle (Voronoi regions)
+fn closest_bary<F: Float>(v: &[[F; 3]; 3], q: &[F; 3]) -> [F; 3] {
+  let o = <F>::from(0).unwrap();
+  let l = <F>::from(1).unwrap();
+  let (ab, ac) = (sub_f3(&v[1], &v[0]), sub_f3(&v[2], &v[0]));
+  let ap = sub_f3(q, &v[0]);
+  let (d1, d2) = (dot_f3(&ab, &ap), dot_f3(&ac, &ap));
+  if d1 <= o && d2 <= o { return [l, o, o]; }
+  let bp = sub_f3(q, &v[1]);
+  let (d3, d4) = (dot_f3(&ab, &bp), dot_f3(&ac, &bp));
+  if d3 >= o && d4 <= d3 { return [o, l, o]; }
+  let vc = d1 * d4 - d3 * d2;
+  if vc <= o && d1 >= o && d3 <= o {
+    let s = d1 / (d1 - d3);
+    return [l - s, s, o];
+  }
+  let cp = sub_f3(q, &v[2]);
+  let (d5, d6) = (dot_f3(&ab, &cp), dot_f3(&ac, &cp));
+  if d6 >= o && d5 <= d6 { return [o, o, l]; }
+  let vb = d5 * d2 - d1 * d6;
+  if vb <= o && d2 >= o && d6 <= o {
+    let s = d2 / (d2 - d6);
+    return [l - s, o, s];
+  }
+  let va = d3 * d6 - d5 * d4;
+  if va <= o && d4 - d3 >= o && d5 - d6 >= o {
+    let s = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+    return [o, l - s, s];
+  }
+  let den = va + vb + vc;
+  if den == o { return [l, o, o]; } // degenerated
+  let (s, t) = (vb / den, vc / den);
+  [l - s - t, s, t]
+}
+
+/// centroid
+fn centroid<F: Float>(v: &[[F; 3]; 3]) -> [F; 3] {
+  let t = <F>::from(3).unwrap();
+  [0, 1, 2].map(|j| (v[0][j] + v[1][j] + v[2][j]) / t)
+}
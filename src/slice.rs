@@ -0,0 +1,342 @@
+//! Slice (contours on the plane) and cut (two capped halves) for Rust
+//!
+//! vertices within the precision from the plane are on the plane
+//! contours are counter clockwise (outer) or clockwise (hole) seen from +n
+//!
+
+use std::collections::HashMap;
+
+use num::Float;
+
+use crate::{merge_vertices, calc_cg_with_volume};
+use crate::{sub_f3, cross_f3, dot_f3};
+use crate::polyhedron::Polyhedron;
+use crate::triangulate::{group_contours, triangulate};
+use crate::error::PHError;
+
+/// Contour closed polygon on the plane (u v coordinates of the Plane)
+pub type Contour<F> = Vec<[F; 2]>;
+
+/// Plane n . x = d
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane<F: Float> {
+  /// unit normal
+  pub n: [F; 3],
+  /// distance from the origin
+  pub d: F,
+  /// u axis on the plane
+  pub u: [F; 3],
+  /// v axis on the plane (u x v = n)
+  pub v: [F; 3]
+}
+
+/// Plane
+impl<F: Float> Plane<F> {
+  /// construct (panic when try_new returns Err)
+  pub fn new(n: [F; 3], d: F) -> Self {
+    Self::try_new(n, d).unwrap()
+  }
+  /// construct
+  /// - n: normal (normalized)
+  /// - d: distance from the origin along n
+  pub fn try_new(n: [F; 3], d: F) -> Result<Self, PHError> {
+    let l = dot_f3(&n, &n).sqrt();
+    if !(l.is_finite() && l > <F>::from(0).unwrap() && d.is_finite()) {
+      return Err(PHError::InvalidParameter(
+        "n must be finite and not zero, d must be finite".to_string()));
+    }
+    let n = n.map(|x| x / l);
+    let a = n.map(|x| x.abs());
+    let k = if a[0] <= a[1] && a[0] <= a[2] { 0 }
+      else if a[1] <= a[2] { 1 } else { 2 }; // least aligned axis
+    let mut e = [<F>::from(0).unwrap(); 3];
+    e[k] = <F>::from(1).unwrap();
+    let u = cross_f3(&e, &n);
+    let lu = dot_f3(&u, &u).sqrt();
+    let u = u.map(|x| x / lu);
+    Ok(Plane{n, d, u, v: cross_f3(&n, &u)})
+  }
+  /// signed distance of the point
+  pub fn dist(&self, q: &[F; 3]) -> F {
+    dot_f3(&self.n, q) - self.d
+  }
+  /// u v coordinates of the point projected on the plane
+  pub fn to_2d(&self, q: &[F; 3]) -> [F; 2] {
+    [dot_f3(&self.u, q), dot_f3(&self.v, q)]
+  }
+  /// the point on the plane from u v coordinates
+  pub fn to_3d(&self, q: &[F; 2]) -> [F; 3] {
+    [0, 1, 2].map(|j| self.n[j] * self.d + self.u[j] * q[0] + self.v[j] * q[1])
+  }
+}
+
+/// signed distances snapped to 0 within p
+fn dists<F: Float>(vtx: &[[F; 3]], n: &[F; 3], d: F, p: F) -> Vec<F> {
+  vtx.iter().map(|v| {
+    let s = dot_f3(n, v) - d;
+    if s.abs() <= p { <F>::from(0).unwrap() } else { s }
+  }).collect()
+}
+
+/// the point on the edge at the plane (same for the both directions)
+fn edge_point<F: Float>(a: &[F; 3], b: &[F; 3], sa: F, sb: F) -> [F; 3] {
+  let ((a, sa), (b, sb)) = match a.partial_cmp(b) {
+  Some(std::cmp::Ordering::Greater) => ((b, sb), (a, sa)),
+  _ => ((a, sa), (b, sb))
+  };
+  let t = sa / (sa - sb);
+  [0, 1, 2].map(|j| a[j] + (b[j] - a[j]) * t)
+}
+
+/// contour loops (3D) on the plane of the welded triangles
+fn loops<F: Float>(tri: &[[u32; 3]], vtx: &[[F; 3]], s: &[F], p: F) ->
+  Vec<Vec<[F; 3]>> {
+  let o = <F>::from(0).unwrap();
+  let below = |i: u32| s[i as usize] < o; // on the plane is above
+  let mut seg = HashMap::<(u32, u32), ((u32, u32), [F; 3])>::new();
+  let mut order = vec![];
+  for t in tri.iter() {
+    let (mut down, mut up) = (None, None);
+    for j in 0..3 {
+      let (a, b) = (t[j], t[(j + 1) % 3]);
+      match (below(a), below(b)) {
+      (false, true) => down = Some((a, b)),
+      (true, false) => up = Some((a, b)),
+      _ => ()
+      }
+    }
+    let (Some((a, b)), Some((c, d))) = (down, up) else { continue; };
+    let (ka, kc) = ((a.min(b), a.max(b)), (c.min(d), c.max(d)));
+    let (va, vb) = (vtx[a as usize], vtx[b as usize]);
+    let q = edge_point(&va, &vb, s[a as usize], s[b as usize]);
+    seg.insert(ka, (kc, q)); // from down edge to up edge
+    order.push(ka);
+  }
+  let mut ls = vec![];
+  for k in order {
+    let Some((mut n, q)) = seg.remove(&k) else { continue; };
+    let mut l = vec![q];
+    while n != k {
+      let Some((m, q)) = seg.remove(&n) else { break; };
+      l.push(q);
+      n = m;
+    }
+    if n != k { continue; } // open
+    l.dedup_by(|a, b| (0..3).all(|j| (a[j] - b[j]).abs() <= p));
+    while l.len() > 1
+    && (0..3).all(|j| (l[0][j] - l[l.len() - 1][j]).abs() <= p) { l.pop(); }
+    if l.len() >= 3 { ls.push(l); }
+  }
+  ls
+}
+
+/// welded triangles (all faces)
+fn welded<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], p: F) ->
+  Vec<[u32; 3]> {
+  let m = merge_vertices(vtx, p);
+  idx.iter().flatten().map(|t| t.map(|i| m[i as usize])).collect()
+}
+
+/// slice (closed contours on the plane)
+/// (vertices on the plane count as above, faces on the plane are skipped)
+/// - idx: index of triangles on each faces
+/// - vtx: vertices (closed and counter clockwise from outside)
+/// - plane: the plane
+/// - p: precision for equality (welds vertices, thickness of the plane)
+pub fn slice<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]],
+  plane: &Plane<F>, p: F) -> Vec<Contour<F>> {
+  let tri = welded(idx, vtx, p);
+  let s = dists(vtx, &plane.n, plane.d, p);
+  loops(&tri, vtx, &s, p).iter().map(|l|
+    l.iter().map(|q| plane.to_2d(q)).collect()).collect()
+}
+
+/// slice at many distances along the same normal
+/// returns contours of each plane (parallel to ds)
+/// - idx: index of triangles on each faces
+/// - vtx: vertices (closed and counter clockwise from outside)
+/// - n: normal of the planes
+/// - ds: distances from the origin along n (e.g. layer heights)
+/// - p: precision for equality (welds vertices, thickness of the plane)
+pub fn slice_many<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]],
+  n: [F; 3], ds: &[F], p: F) -> Result<Vec<Vec<Contour<F>>>, PHError> {
+  let tri = welded(idx, vtx, p);
+  let mut planes = vec![];
+  for &d in ds.iter() { planes.push(Plane::try_new(n, d)?); }
+  let h = vtx.iter().map(|v| dot_f3(&planes.first().map_or(n, |pl| pl.n), v))
+    .collect::<Vec<_>>();
+  let span = tri.iter().map(|t| t.map(|i| h[i as usize]))
+    .map(|[a, b, c]| (a.min(b).min(c), a.max(b).max(c))).collect::<Vec<_>>();
+  Ok(planes.iter().map(|pl| {
+    let ts = tri.iter().zip(span.iter()).filter(|(_, &(lo, hi))|
+      lo - p <= pl.d && pl.d <= hi + p).map(|(t, _)| *t).collect::<Vec<_>>();
+    let s = dists(vtx, &pl.n, pl.d, p);
+    loops(&ts, vtx, &s, p).iter().map(|l|
+      l.iter().map(|q| pl.to_2d(q)).collect()).collect()
+  }).collect())
+}
+
+/// vertex of the clipped polygon (Ok: vertex id, Err: edge (sorted) point)
+type Clipped = Result<u32, (u32, u32)>;
+
+/// clip the polygon to the side (sg: 1 above, -1 below)
+/// returns vertices and strictly in the side
+fn clip<F: Float>(t: &[u32; 3], s: &[F], sg: F) -> (Vec<Clipped>, bool) {
+  let o = <F>::from(0).unwrap();
+  let mut r = vec![];
+  let mut strict = false;
+  for j in 0..3 {
+    let (a, b) = (t[j], t[(j + 1) % 3]);
+    let (sa, sb) = (s[a as usize] * sg, s[b as usize] * sg);
+    if sa >= o { r.push(Ok(a)); }
+    if sa > o { strict = true; }
+    if (sa < o && sb > o) || (sa > o && sb < o) {
+      r.push(Err((a.min(b), a.max(b))));
+    }
+  }
+  (r, strict)
+}
+
+/// cap loops closing the open edges of the clipped half
+/// (counter clockwise (outer) or clockwise (hole) seen from +n)
+fn cap_loops<F: Float>(tri: &[Vec<[u32; 3]>], vs: &[[F; 3]],
+  plane: &Plane<F>, sg: F, p: F) -> Result<Vec<Vec<[F; 3]>>, PHError> {
+  let m = merge_vertices(vs, p);
+  let mut cnt = HashMap::<(u32, u32), i32>::new(); // a b minus b a
+  let mut order = vec![];
+  for t in tri.iter().flatten() {
+    let t = t.map(|i| m[i as usize]);
+    for j in 0..3 {
+      let (a, b) = (t[j], t[(j + 1) % 3]);
+      if a == b { continue; }
+      *cnt.entry((a, b)).or_insert(0) += 1;
+      *cnt.entry((b, a)).or_insert(0) -= 1;
+      order.push((a, b));
+    }
+  }
+  let mut next = HashMap::<u32, Vec<u32>>::new(); // reversed open edges
+  let mut starts = vec![];
+  for e in order {
+    for _ in 0..cnt.insert(e, 0).unwrap_or(0) {
+      next.entry(e.1).or_default().push(e.0);
+      starts.push(e.1);
+    }
+  }
+  let open = || PHError::Degenerate(
+    "cut leaves open edges off the plane (mesh is not closed)".to_string());
+  let mut ls = vec![];
+  for k0 in starts {
+    let (mut l, mut k) = (vec![], k0);
+    while let Some(n) = next.get_mut(&k).and_then(|v| v.pop()) {
+      l.push(vs[k as usize]);
+      k = n;
+      if k == k0 { break; }
+    }
+    if l.is_empty() { continue; }
+    if k != k0 || l.iter().any(|q| plane.dist(q).abs() > p) {
+      return Err(open());
+    }
+    if sg > <F>::from(0).unwrap() { l.reverse(); } // cap faces to -n
+    if l.len() >= 3 { ls.push(l); }
+  }
+  Ok(ls)
+}
+
+/// cut into the two closed halves (above, below) capped on the plane
+/// (faces keep the index of the source and may be empty,
+/// faces on the plane stay in the half they bound,
+/// caps close the open edges and are appended as the last faces,
+/// cap uv is planar in [0, 1])
+/// - idx: index of triangles on each faces
+/// - uv: uv of triangles on each faces (may be empty)
+/// - vtx: vertices (closed and counter clockwise from outside)
+/// - plane: the plane (above is +n side)
+/// - p: precision for equality (welds vertices, thickness of the plane)
+pub fn cut<F: Float + std::fmt::Debug + std::iter::Sum>(idx: &[Vec<[u32; 3]>],
+  uv: &[Vec<[[F; 2]; 3]>], vtx: &[[F; 3]], plane: &Plane<F>, p: F) ->
+  Result<(Polyhedron<F>, Polyhedron<F>), PHError> {
+  let o = <F>::from(0).unwrap();
+  let s = dists(vtx, &plane.n, plane.d, p);
+  let mut halves = vec![];
+  for sg in [<F>::from(1).unwrap(), <F>::from(-1).unwrap()] {
+    let mut vs = vec![];
+    let mut ids = HashMap::<Clipped, u32>::new();
+    let mut tri = vec![];
+    let mut uvs = vec![];
+    for (fi, f) in idx.iter().enumerate() {
+      let (mut ft, mut fu) = (vec![], vec![]);
+      for (ti, t) in f.iter().enumerate() {
+        let (r, strict) = clip(t, &s, sg);
+        if r.len() < 3 { continue; }
+        if !strict { // on the plane: keeps when the face looks to the other
+          let [a, b, c] = t.map(|i| vtx[i as usize]);
+          let n = cross_f3(&sub_f3(&b, &a), &sub_f3(&c, &a));
+          if dot_f3(&n, &plane.n) * sg >= o { continue; }
+        }
+        let w = |k: &Clipped| -> [F; 2] {
+          let pos = |i: u32| t.iter().position(|&j| j == i).unwrap();
+          match *k {
+          Ok(i) => uv[fi][ti][pos(i)],
+          Err((a, b)) => {
+            let (sa, sb) = (s[a as usize], s[b as usize]);
+            let (ua, ub) = (uv[fi][ti][pos(a)], uv[fi][ti][pos(b)]);
+            let t = sa / (sa - sb);
+            [0, 1].map(|j| ua[j] + (ub[j] - ua[j]) * t)
+          }
+          }
+        };
+        let k = r.iter().map(|&k| *ids.entry(k).or_insert_with(|| {
+          vs.push(match k {
+          Ok(i) => vtx[i as usize],
+          Err((a, b)) => edge_point(&vtx[a as usize], &vtx[b as usize],
+            s[a as usize], s[b as usize])
+          });
+          vs.len() as u32 - 1
+        })).collect::<Vec<_>>();
+        for j in 1..k.len() - 1 {
+          ft.push([k[0], k[j], k[j + 1]]);
+          if !uv.is_empty() { fu.push([w(&r[0]), w(&r[j]), w(&r[j + 1])]); }
+        }
+      }
+      tri.push(ft);
+      uvs.push(fu);
+    }
+    if tri.iter().all(|f| f.is_empty()) {
+      return Err(PHError::Degenerate(
+        "plane does not cut the mesh".to_string()));
+    }
+    let caps = cap_loops(&tri, &vs, plane, sg, p)?;
+    let cs = caps.iter().map(|l|
+      l.iter().map(|q| plane.to_2d(q)).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    let (lo, hi) = cs.iter().flatten().fold(
+      ([F::infinity(); 2], [F::neg_infinity(); 2]), |(lo, hi), q|
+      ([lo[0].min(q[0]), lo[1].min(q[1])],
+       [hi[0].max(q[0]), hi[1].max(q[1])]));
+    let w = (hi[0] - lo[0]).max(hi[1] - lo[1]);
+    for (g, hs) in group_contours(&cs) {
+      let ks = [g].into_iter().chain(hs.iter().cloned()).collect::<Vec<_>>();
+      let b = vs.len() as u32;
+      let mut quv = vec![];
+      for &k in ks.iter() {
+        vs.extend(caps[k].iter().cloned());
+        quv.extend(cs[k].iter().map(|q|
+          [(q[0] - lo[0]) / w, <F>::from(1).unwrap() - (q[1] - lo[1]) / w]));
+      }
+      let hl = hs.iter().map(|&h| cs[h].clone()).collect::<Vec<_>>();
+      let ts = triangulate(&cs[g], &hl).into_iter().map(|t| match sg > o {
+        true => [t[0], t[2], t[1]], // faces to -n
+        false => t
+      }).collect::<Vec<_>>();
+      if !uv.is_empty() {
+        uvs.push(ts.iter().map(|t| t.map(|i| quv[i as usize])).collect());
+      }
+      tri.push(ts.iter().map(|t| t.map(|i| b + i)).collect());
+    }
+    if uv.is_empty() { uvs.clear(); }
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vs, p);
+    halves.push(Polyhedron{vtx: vs, tri, uv: uvs, vol, center: false});
+  }
+  let below = halves.pop().unwrap();
+  Ok((halves.pop().unwrap(), below))
+}
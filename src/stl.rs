@@ -0,0 +1,168 @@
+//! STL (ASCII and binary) on polyhedron faces for Rust
+//!
+
+use std::io::{Read, Write, Error, ErrorKind};
+use std::path::Path;
+
+use num::Float;
+
+use crate::polyhedron::{TUV, Polyhedron};
+use crate::{tri_normal, merge_vertices, calc_cg_with_volume, f_to_f64};
+use crate::error::check_index;
+
+/// triangles with facet normal [n, v0, v1, v2]
+pub fn facets<F, T>(ph: &T) -> Vec<[[F; 3]; 4]>
+  where F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized {
+  let vtx = ph.ref_vtx();
+  ph.ref_tri().iter().flat_map(|f|
+    f.iter().map(|t| {
+      let vs = [vtx[t[0] as usize], vtx[t[1] as usize], vtx[t[2] as usize]];
+      [tri_normal(&vs), vs[0], vs[1], vs[2]]
+    }).collect::<Vec<_>>()
+  ).collect()
+}
+
+/// write binary stl
+pub fn write_stl_binary<F, T, W>(w: &mut W, ph: &T) -> std::io::Result<()>
+  where F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized,
+  W: Write {
+  let fs = facets(ph);
+  let mut header = [0u8; 80];
+  let s = b"polyhedron-faces";
+  header[..s.len()].copy_from_slice(s);
+  w.write_all(&header)?;
+  w.write_all(&(fs.len() as u32).to_le_bytes())?;
+  for f in fs.iter() {
+    for v in f.iter() {
+      for p in v.iter() { w.write_all(&p.to_f32().unwrap().to_le_bytes())?; }
+    }
+    w.write_all(&0u16.to_le_bytes())?; // attribute byte count
+  }
+  Ok(())
+}
+
+/// write ascii stl
+pub fn write_stl_ascii<F, T, W>(w: &mut W, ph: &T, name: &str) ->
+  std::io::Result<()>
+  where F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized,
+  W: Write {
+  writeln!(w, "solid {}", name)?;
+  for f in facets(ph).iter() {
+    let n = f_to_f64(&f[0]);
+    writeln!(w, "  facet normal {:e} {:e} {:e}", n[0], n[1], n[2])?;
+    writeln!(w, "    outer loop")?;
+    for v in f[1..].iter() {
+      let v = f_to_f64(v);
+      writeln!(w, "      vertex {:e} {:e} {:e}", v[0], v[1], v[2])?;
+    }
+    writeln!(w, "    endloop")?;
+    writeln!(w, "  endfacet")?;
+  }
+  writeln!(w, "endsolid {}", name)?;
+  Ok(())
+}
+
+/// save stl
+/// - binary: true: binary, false: ascii
+pub fn save_stl<F, T, P>(path: P, ph: &T, binary: bool) -> std::io::Result<()>
+  where F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized,
+  P: AsRef<Path> {
+  let path = path.as_ref();
+  let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("polyhedron");
+  let mut w = std::io::BufWriter::new(std::fs::File::create(path)?);
+  match binary {
+  true => write_stl_binary(&mut w, ph)?,
+  false => write_stl_ascii(&mut w, ph, name)?
+  }
+  w.flush()
+}
+
+/// parse triangles from binary stl (facet normal is ignored)
+pub fn parse_stl_binary(buf: &[u8]) -> std::io::Result<Vec<[[f32; 3]; 3]>> {
+  let e = || Error::new(ErrorKind::InvalidData, "binary stl is too short");
+  if buf.len() < 84 { return Err(e()); }
+  let n = u32::from_le_bytes(buf[80..84].try_into().unwrap()) as usize;
+  if buf.len() < 84 + n * 50 { return Err(e()); }
+  let f = |o: usize| f32::from_le_bytes(buf[o..o+4].try_into().unwrap());
+  Ok((0..n).map(|i| {
+    let o = 84 + i * 50 + 12; // skip normal
+    let v = |k: usize| [f(o + k * 12), f(o + k * 12 + 4), f(o + k * 12 + 8)];
+    [v(0), v(1), v(2)]
+  }).collect())
+}
+
+/// parse triangles from ascii stl (facet normal is ignored)
+pub fn parse_stl_ascii(s: &str) -> std::io::Result<Vec<[[f32; 3]; 3]>> {
+  let e = |m: &str| Error::new(ErrorKind::InvalidData, m.to_string());
+  let mut tris = vec![];
+  let mut vs = vec![];
+  for l in s.lines() {
+    let mut w = l.split_whitespace();
+    match w.next() {
+    Some("vertex") => {
+      let v = w.map(|p| p.parse::<f32>().map_err(|_| e("bad vertex")))
+        .collect::<Result<Vec<_>, _>>()?;
+      if v.len() != 3 { return Err(e("vertex needs 3 values")); }
+      vs.push([v[0], v[1], v[2]]);
+    },
+    Some("endloop") => {
+      if vs.len() != 3 { return Err(e("facet needs 3 vertices")); }
+      tris.push([vs[0], vs[1], vs[2]]);
+      vs.clear();
+    },
+    _ => ()
+    }
+  }
+  Ok(tris)
+}
+
+/// rebuild polyhedron from triangles (tri: Vec n of Vec 1 indexed triangles)
+/// - p: precision to weld duplicate vertices
+pub fn from_triangles<F: Float + std::fmt::Debug + std::iter::Sum>(
+  tris: &[[[F; 3]; 3]], p: F) -> std::io::Result<Polyhedron<F>> {
+  let vs = tris.iter().flat_map(|t| t.to_vec()).collect::<Vec<_>>();
+  check_index(vs.len() as u64).map_err(|e|
+    Error::new(ErrorKind::InvalidData, e))?;
+  let m = merge_vertices(&vs, p);
+  let mut remap = vec![0u32; vs.len()];
+  let mut vtx = vec![];
+  for (i, &k) in m.iter().enumerate() {
+    if k as usize == i { remap[i] = vtx.len() as u32; vtx.push(vs[i]); }
+    else { remap[i] = remap[k as usize]; }
+  }
+  let tri = (0..tris.len()).filter_map(|i| {
+    let t = [remap[i * 3], remap[i * 3 + 1], remap[i * 3 + 2]];
+    if t[0] == t[1] || t[1] == t[2] || t[2] == t[0] { None } // degenerated
+    else { Some(vec![t]) }
+  }).collect::<Vec<_>>();
+  let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+  Ok(Polyhedron{vtx, tri, uv: vec![], vol, center: false})
+}
+
+/// read stl (ascii or binary) and weld duplicate vertices
+/// - p: precision to weld duplicate vertices
+pub fn read_stl<F: Float + std::fmt::Debug + std::iter::Sum, R: Read>(
+  r: &mut R, p: F) -> std::io::Result<Polyhedron<F>> {
+  let mut buf = vec![];
+  r.read_to_end(&mut buf)?;
+  let binary = buf.len() >= 84 && buf.len() ==
+    84 + 50 * u32::from_le_bytes(buf[80..84].try_into().unwrap()) as usize;
+  let tris = match binary {
+  true => parse_stl_binary(&buf)?,
+  false => match std::str::from_utf8(&buf) {
+    Ok(s) if s.trim_start().starts_with("solid") => parse_stl_ascii(s)?,
+    _ => parse_stl_binary(&buf)?
+    }
+  };
+  let tris = tris.into_iter().map(|t|
+    t.map(|v| v.map(|f| <F>::from(f).unwrap()))).collect::<Vec<_>>();
+  from_triangles(&tris, p)
+}
+
+/// load stl (ascii or binary) and weld duplicate vertices
+/// - p: precision to weld duplicate vertices
+pub fn load_stl<F: Float + std::fmt::Debug + std::iter::Sum, P: AsRef<Path>>(
+  path: P, p: F) -> std::io::Result<Polyhedron<F>> {
+  let mut r = std::io::BufReader::new(std::fs::File::open(path)?);
+  read_stl(&mut r, p)
+}
@@ -0,0 +1,313 @@
+//! Subdivision surfaces (Loop and Catmull-Clark) on polyhedron faces for Rust
+//!
+//! - vtx are welded with the precision and subdivided on the welded topology
+//!   (open edges are creases)
+//! - uv are subdivided with the same rules on the uv topology
+//!   (uv seams and open edges are creases, so each island keeps its border)
+//! - each new triangle stays in the face group of its parent
+//!
+
+use std::collections::{HashMap, HashSet};
+
+use num::Float;
+
+use crate::polyhedron::{TUV, Polyhedron};
+use crate::{merge_vertices, calc_cg_with_volume, prec_eq};
+use crate::error::{PHError, check_index};
+
+/// faces on each edge (key is sorted)
+type EdgeFaces = HashMap<(u32, u32), Vec<usize>>;
+
+/// polygon mesh with the attribute on each vertex (vtx or uv)
+#[derive(Debug, Clone)]
+struct Mesh<F: Float, const D: usize> {
+  /// values
+  vs: Vec<[F; D]>,
+  /// faces: Vec n of counter clockwise polygon
+  faces: Vec<Vec<u32>>
+}
+
+/// Mesh
+impl<F: Float, const D: usize> Mesh<F, D> {
+  /// faces on each edge (key is sorted) with the edges in the found order
+  fn edge_faces(&self) -> (EdgeFaces, Vec<(u32, u32)>) {
+    let mut ef = EdgeFaces::new();
+    let mut es = vec![];
+    for (fi, f) in self.faces.iter().enumerate() {
+      for k in 0..f.len() {
+        let (a, b) = (f[k], f[(k + 1) % f.len()]);
+        let e = (a.min(b), a.max(b));
+        ef.entry(e).or_insert_with(|| { es.push(e); vec![] }).push(fi);
+      }
+    }
+    (ef, es)
+  }
+
+  /// neighbours and crease neighbours (open or non manifold edges)
+  fn neighbours(&self, ef: &EdgeFaces,
+    es: &[(u32, u32)]) -> (Vec<Vec<u32>>, Vec<Vec<u32>>) {
+    let mut nb = vec![vec![]; self.vs.len()];
+    let mut cr = vec![vec![]; self.vs.len()];
+    for &(a, b) in es.iter() {
+      nb[a as usize].push(b);
+      nb[b as usize].push(a);
+      if ef[&(a, b)].len() != 2 {
+        cr[a as usize].push(b);
+        cr[b as usize].push(a);
+      }
+    }
+    (nb, cr)
+  }
+
+  /// crease vertex rule (None when smooth)
+  fn crease(&self, i: usize, nb: &[u32], cr: &[u32]) -> Option<[F; D]> {
+    let v = &self.vs[i];
+    match cr.len() {
+    0 | 1 => None, // smooth (or dart)
+    2 if nb.len() > 2 => {
+      let (c0, c1) = (&self.vs[cr[0] as usize], &self.vs[cr[1] as usize]);
+      Some(lin(&[(v, 0.75), (c0, 0.125), (c1, 0.125)]))
+    },
+    _ => Some(*v) // corner (or the only one face)
+    }
+  }
+
+  /// Loop subdivision (triangles) with the parent face of new faces
+  fn loop_step(&self) -> (Self, Vec<usize>) {
+    let (ef, es) = self.edge_faces();
+    let (nb, cr) = self.neighbours(&ef, &es);
+    let n = self.vs.len() as u32;
+    let eid = es.iter().enumerate().map(|(i, &e)| (e, n + i as u32))
+      .collect::<HashMap<_, _>>();
+    let mut vs = (0..self.vs.len()).map(|i| {
+      self.crease(i, &nb[i], &cr[i]).unwrap_or_else(|| {
+        let k = nb[i].len() as f64;
+        let t = 3.0 / 8.0 + (2.0 * std::f64::consts::PI / k).cos() / 4.0;
+        let b = (5.0 / 8.0 - t * t) / k;
+        let mut ws = vec![(&self.vs[i], 1.0 - k * b)];
+        ws.extend(nb[i].iter().map(|&j| (&self.vs[j as usize], b)));
+        lin(&ws)
+      })
+    }).collect::<Vec<_>>();
+    vs.extend(es.iter().map(|&(a, b)| {
+      let (va, vb) = (&self.vs[a as usize], &self.vs[b as usize]);
+      match &ef[&(a, b)][..] {
+      &[f0, f1] => {
+        let opp = |fi: usize| self.faces[fi].iter()
+          .find(|&&c| c != a && c != b).map(|&c| &self.vs[c as usize])
+          .unwrap();
+        lin(&[(va, 0.375), (vb, 0.375), (opp(f0), 0.125), (opp(f1), 0.125)])
+      },
+      _ => lin(&[(va, 0.5), (vb, 0.5)])
+      }
+    }));
+    let e = |a: u32, b: u32| eid[&(a.min(b), a.max(b))];
+    let mut faces = vec![];
+    let mut parent = vec![];
+    for (fi, f) in self.faces.iter().enumerate() {
+      let (a, b, c) = (f[0], f[1], f[2]);
+      let (ab, bc, ca) = (e(a, b), e(b, c), e(c, a));
+      faces.extend([
+        vec![a, ab, ca], vec![b, bc, ab], vec![c, ca, bc], vec![ab, bc, ca]]);
+      parent.extend([fi; 4]);
+    }
+    (Mesh{vs, faces}, parent)
+  }
+
+  /// Catmull-Clark subdivision (polygons to quads) with the parent face
+  fn catmull_clark_step(&self) -> (Self, Vec<usize>) {
+    let (ef, es) = self.edge_faces();
+    let (nb, cr) = self.neighbours(&ef, &es);
+    let n = self.vs.len() as u32;
+    let nf = self.faces.len() as u32;
+    let eid = es.iter().enumerate().map(|(i, &e)| (e, n + nf + i as u32))
+      .collect::<HashMap<_, _>>();
+    let fps = self.faces.iter().map(|f| {
+      let w = 1.0 / f.len() as f64;
+      lin(&f.iter().map(|&i| (&self.vs[i as usize], w)).collect::<Vec<_>>())
+    }).collect::<Vec<_>>();
+    let mut vf = vec![vec![]; self.vs.len()];
+    for (fi, f) in self.faces.iter().enumerate() {
+      for &i in f.iter() { vf[i as usize].push(fi); }
+    }
+    let mut vs = (0..self.vs.len()).map(|i| {
+      self.crease(i, &nb[i], &cr[i]).unwrap_or_else(|| {
+        // (Q + 2R + (k - 3)S) / k
+        let k = nb[i].len() as f64;
+        let q = 1.0 / (vf[i].len() as f64 * k);
+        let r = 1.0 / (k * k); // 2 * mid / k / k
+        let mut ws = vec![(&self.vs[i], (k - 3.0) / k + r * k)];
+        ws.extend(vf[i].iter().map(|&fi| (&fps[fi], q)));
+        ws.extend(nb[i].iter().map(|&j| (&self.vs[j as usize], r)));
+        lin(&ws)
+      })
+    }).collect::<Vec<_>>();
+    vs.extend(fps.iter().copied());
+    vs.extend(es.iter().map(|&(a, b)| {
+      let (va, vb) = (&self.vs[a as usize], &self.vs[b as usize]);
+      match &ef[&(a, b)][..] {
+      &[f0, f1] =>
+        lin(&[(va, 0.25), (vb, 0.25), (&fps[f0], 0.25), (&fps[f1], 0.25)]),
+      _ => lin(&[(va, 0.5), (vb, 0.5)])
+      }
+    }));
+    let e = |a: u32, b: u32| eid[&(a.min(b), a.max(b))];
+    let mut faces = vec![];
+    let mut parent = vec![];
+    for (fi, f) in self.faces.iter().enumerate() {
+      let m = f.len();
+      for k in 0..m {
+        let (p, c, q) = (f[(k + m - 1) % m], f[k], f[(k + 1) % m]);
+        faces.push(vec![c, e(c, q), n + fi as u32, e(p, c)]);
+        parent.push(fi);
+      }
+    }
+    (Mesh{vs, faces}, parent)
+  }
+}
+
+/// linear combination
+fn lin<F: Float, const D: usize>(ws: &[(&[F; D], f64)]) -> [F; D] {
+  let mut v = [<F>::from(0).unwrap(); D];
+  for (p, w) in ws.iter() {
+    let w = <F>::from(*w).unwrap();
+    for k in 0..D { v[k] = v[k] + p[k] * w; }
+  }
+  v
+}
+
+/// vtx mesh, uv mesh (None when no uv) and the face group of each face
+type Meshes<F> = (Mesh<F, 3>, Option<Mesh<F, 2>>, Vec<usize>);
+
+/// welded meshes (polygon: each face group to the one polygon when possible)
+fn meshes<F: Float, T: TUV<F> + ?Sized>(ph: &T, p: F, polygon: bool) ->
+  Meshes<F> {
+  let vtx = ph.ref_vtx();
+  let uv = ph.ref_uv();
+  let has_uv = !uv.is_empty();
+  let m = merge_vertices(vtx, p);
+  let mut ids = HashMap::<u32, u32>::new();
+  let mut vs = vec![];
+  let pid = m.iter().map(|&k| *ids.entry(k).or_insert_with(|| {
+    vs.push(vtx[k as usize]);
+    vs.len() as u32 - 1
+  })).collect::<Vec<_>>();
+  // uv id by (vertex id, uv)
+  let mut uvs = Vec::<[F; 2]>::new();
+  let mut uvids = HashMap::<u32, Vec<u32>>::new();
+  let mut uid = |i: u32, t: &[F; 2]| {
+    let us = uvids.entry(i).or_default();
+    match us.iter().find(|&&u| prec_eq(&uvs[u as usize], p, t)) {
+    Some(&u) => u,
+    None => {
+      uvs.push(*t);
+      us.push(uvs.len() as u32 - 1);
+      uvs.len() as u32 - 1
+    }
+    }
+  };
+  let (mut faces, mut ufaces, mut groups) = (vec![], vec![], vec![]);
+  for (fi, f) in ph.ref_tri().iter().enumerate() {
+    // corners (vertex id, uv id) of the non degenerated triangles
+    let ts = f.iter().enumerate().filter_map(|(ti, t)| {
+      let t = t.map(|i| pid[i as usize]);
+      if t[0] == t[1] || t[1] == t[2] || t[2] == t[0] { return None; }
+      let u = match has_uv {
+      true => [0, 1, 2].map(|k| uid(t[k], &uv[fi][ti][k])),
+      false => [0; 3]
+      };
+      Some((t, u))
+    }).collect::<Vec<_>>();
+    let poly = match polygon { true => boundary(&ts), false => None };
+    match poly {
+    Some((f, u)) => { faces.push(f); ufaces.push(u); groups.push(fi); },
+    None => for (t, u) in ts.into_iter() {
+      faces.push(t.to_vec()); ufaces.push(u.to_vec()); groups.push(fi);
+    }
+    }
+  }
+  let um = match has_uv {
+  true => Some(Mesh{vs: uvs, faces: ufaces}),
+  false => None
+  };
+  (Mesh{vs, faces}, um, groups)
+}
+
+/// the one boundary polygon of the triangles (vertex ids, uv ids)
+fn boundary(ts: &[([u32; 3], [u32; 3])]) -> Option<(Vec<u32>, Vec<u32>)> {
+  if ts.len() == 1 { return Some((ts[0].0.to_vec(), ts[0].1.to_vec())); }
+  let mut es = HashSet::<(u32, u32)>::new();
+  let mut us = HashMap::<u32, u32>::new();
+  for (t, u) in ts.iter() {
+    for k in 0..3 {
+      if *us.entry(t[k]).or_insert(u[k]) != u[k] { return None; } // seam
+      let (a, b) = (t[k], t[(k + 1) % 3]);
+      if !es.remove(&(b, a)) { es.insert((a, b)); } // inside the face
+    }
+  }
+  let next = es.iter().copied().collect::<HashMap<_, _>>();
+  if next.len() != es.len() { return None; }
+  let &s = next.keys().min()?;
+  let mut poly = vec![s];
+  let mut a = next[&s];
+  while a != s && poly.len() < next.len() {
+    poly.push(a);
+    a = next[&a];
+  }
+  if a != s || poly.len() != es.len() { return None; }
+  let uvs = poly.iter().map(|i| us[i]).collect();
+  Some((poly, uvs))
+}
+
+/// subdivide n times and build the polyhedron (fan triangles of each face)
+fn subdivide<F: Float + std::fmt::Debug + std::iter::Sum, T: TUV<F> + ?Sized>(
+  ph: &T, n: u16, p: F, polygon: bool) -> Result<Polyhedron<F>, PHError> {
+  let (mut vm, mut um, mut groups) = meshes(ph, p, polygon);
+  for _ in 0..n {
+    let ne = vm.faces.iter().map(|f| f.len() as u64).sum::<u64>(); // 2E
+    let nv = vm.vs.len() as u64 + ne;
+    check_index(if polygon { nv + vm.faces.len() as u64 } else { nv })?;
+    let (v, pa) = match polygon {
+    true => vm.catmull_clark_step(),
+    false => vm.loop_step()
+    };
+    um = um.map(|u| match polygon {
+    true => u.catmull_clark_step().0,
+    false => u.loop_step().0
+    });
+    groups = pa.iter().map(|&k| groups[k]).collect();
+    vm = v;
+  }
+  let mut tri = vec![vec![]; ph.ref_tri().len()];
+  let mut uv = vec![vec![]; match um { Some(_) => tri.len(), None => 0 }];
+  for (k, f) in vm.faces.iter().enumerate() {
+    let g = groups[k];
+    for j in 1..f.len()-1 {
+      tri[g].push([f[0], f[j], f[j + 1]]);
+      if let Some(u) = &um {
+        let uf = &u.faces[k];
+        uv[g].push([0, j, j + 1].map(|i| u.vs[uf[i] as usize]));
+      }
+    }
+  }
+  let vtx = vm.vs;
+  let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, p);
+  Ok(Polyhedron{vtx, tri, uv, vol, center: false})
+}
+
+/// Loop subdivision on the triangles
+/// - n: number of iterations
+/// - p: precision to weld duplicate vertices and uv
+pub fn loop_subdivide<F: Float + std::fmt::Debug + std::iter::Sum,
+  T: TUV<F> + ?Sized>(ph: &T, n: u16, p: F) -> Result<Polyhedron<F>, PHError> {
+  subdivide(ph, n, p, false)
+}
+
+/// Catmull-Clark subdivision on the polygon of each face group
+/// (each triangle when the face group is not the one polygon)
+/// - n: number of iterations
+/// - p: precision to weld duplicate vertices and uv
+pub fn catmull_clark<F: Float + std::fmt::Debug + std::iter::Sum,
+  T: TUV<F> + ?Sized>(ph: &T, n: u16, p: F) -> Result<Polyhedron<F>, PHError> {
+  subdivide(ph, n, p, true)
+}
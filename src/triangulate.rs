@@ -0,0 +1,187 @@
+//! Triangulate (ear clipping) polygons with holes for Rust
+//!
+//! outer polygon is counter clockwise and holes are clockwise
+//! (reversed when given in the other direction)
+//!
+
+use num::Float;
+
+/// signed area of the polygon (positive when counter clockwise)
+pub fn polygon_area<F: Float>(ps: &[[F; 2]]) -> F {
+  let n = ps.len();
+  (0..n).fold(<F>::from(0).unwrap(), |s, i| {
+    let (a, b) = (ps[i], ps[(i + 1) % n]);
+    s + a[0] * b[1] - a[1] * b[0]
+  }) / <F>::from(2).unwrap()
+}
+
+/// point in polygon (even odd rule)
+pub fn point_in_polygon<F: Float>(q: &[F; 2], ps: &[[F; 2]]) -> bool {
+  let n = ps.len();
+  let mut c = false;
+  for i in 0..n {
+    let (a, b) = (ps[i], ps[(i + n - 1) % n]);
+    if (a[1] > q[1]) != (b[1] > q[1])
+    && q[0] < (b[0] - a[0]) * (q[1] - a[1]) / (b[1] - a[1]) + a[0] {
+      c = !c;
+    }
+  }
+  c
+}
+
+/// group the contours to outer polygons and their holes
+/// (outer: positive area, hole: negative area in the smallest outer)
+/// returns (outer index, hole indices)
+pub fn group_contours<F: Float>(cs: &[Vec<[F; 2]>]) ->
+  Vec<(usize, Vec<usize>)> {
+  let o = <F>::from(0).unwrap();
+  let area = cs.iter().map(|c| polygon_area(c)).collect::<Vec<_>>();
+  let mut gs = (0..cs.len()).filter(|&i| area[i] > o)
+    .map(|i| (i, vec![])).collect::<Vec<_>>();
+  for h in (0..cs.len()).filter(|&i| area[i] < o) {
+    let Some(q) = cs[h].first() else { continue; };
+    let g = gs.iter_mut().filter(|(i, _)| point_in_polygon(q, &cs[*i]))
+      .min_by(|(i, _), (j, _)| area[*i].partial_cmp(&area[*j])
+        .unwrap_or(std::cmp::Ordering::Equal));
+    if let Some((_, hs)) = g { hs.push(h); }
+  }
+  gs
+}
+
+/// cross of (b - a) and (c - a)
+fn cross3<F: Float>(a: &[F; 2], b: &[F; 2], c: &[F; 2]) -> F {
+  (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// point in or on the triangle (counter clockwise)
+fn in_tri<F: Float>(q: &[F; 2], a: &[F; 2], b: &[F; 2], c: &[F; 2]) -> bool {
+  let o = <F>::from(0).unwrap();
+  cross3(a, b, q) >= o && cross3(b, c, q) >= o && cross3(c, a, q) >= o
+}
+
+/// triangulate the polygon with holes (ear clipping, holes are bridged)
+/// returns counter clockwise triangles of the index in the points
+/// [outer..., holes[0]..., holes[1]..., ...]
+/// - outer: outer polygon
+/// - holes: holes in the outer polygon
+pub fn triangulate<F: Float>(outer: &[[F; 2]], holes: &[Vec<[F; 2]>]) ->
+  Vec<[u32; 3]> {
+  let o = <F>::from(0).unwrap();
+  let mut pts = outer.to_vec();
+  let mut ring = (0..outer.len() as u32).collect::<Vec<_>>();
+  if polygon_area(outer) < o { ring.reverse(); }
+  let mut hs = vec![];
+  for h in holes.iter() {
+    let s = pts.len() as u32;
+    pts.extend(h.iter().cloned());
+    let mut r = (s..s + h.len() as u32).collect::<Vec<_>>();
+    if polygon_area(h) > o { r.reverse(); }
+    if r.len() >= 3 { hs.push(r); }
+  }
+  hs.sort_by(|a, b| {
+    let mx = |r: &Vec<u32>| r.iter().map(|&i| pts[i as usize][0])
+      .fold(F::neg_infinity(), |m, x| m.max(x));
+    mx(b).partial_cmp(&mx(a)).unwrap_or(std::cmp::Ordering::Equal)
+  });
+  for h in hs.iter() { ring = bridge(&pts, ring, h); }
+  let mut tri = ear_clip(&pts, ring);
+  flip_flat(&pts, &mut tri);
+  tri
+}
+
+/// flip the flat triangles (collinear corners left by the collinear
+/// vertices of the polygon) with the neighbour across the longest edge
+fn flip_flat<F: Float>(pts: &[[F; 2]], tri: &mut [[u32; 3]]) {
+  let p = |i: u32| pts[i as usize];
+  let d2 = |a: u32, b: u32| {
+    let (a, b) = (p(a), p(b));
+    (b[0] - a[0]) * (b[0] - a[0]) + (b[1] - a[1]) * (b[1] - a[1])
+  };
+  let eps = <F>::from(1e-12).unwrap();
+  let mut skip = vec![false; tri.len()];
+  for _ in 0..3 * tri.len() {
+    let Some((k, j)) = (0..tri.len()).filter(|&k| !skip[k]).find_map(|k| {
+      let t = tri[k];
+      let j = (0..3).max_by(|&i, &j| d2(t[i], t[(i + 1) % 3])
+        .partial_cmp(&d2(t[j], t[(j + 1) % 3]))
+        .unwrap_or(std::cmp::Ordering::Equal)).unwrap();
+      let l = d2(t[j], t[(j + 1) % 3]);
+      (cross3(&p(t[0]), &p(t[1]), &p(t[2])).abs() <= eps * l).then_some((k, j))
+    }) else { break; };
+    let (a, c, b) = (tri[k][j], tri[k][(j + 1) % 3], tri[k][(j + 2) % 3]);
+    let Some((m, i)) = (0..tri.len()).find_map(|m| (0..3).find(|&i|
+      tri[m][i] == c && tri[m][(i + 1) % 3] == a).map(|i| (m, i))) else {
+      skip[k] = true; // on the outline
+      continue;
+    };
+    let d = tri[m][(i + 2) % 3];
+    tri[k] = [a, d, b];
+    tri[m] = [b, d, c];
+  }
+}
+
+/// bridge the hole to the ring (from the rightmost vertex of the hole)
+fn bridge<F: Float>(pts: &[[F; 2]], ring: Vec<u32>, h: &[u32]) -> Vec<u32> {
+  let p = |i: u32| pts[i as usize];
+  let hm = (0..h.len()).max_by(|&a, &b| p(h[a])[0].partial_cmp(&p(h[b])[0])
+    .unwrap_or(std::cmp::Ordering::Equal)).unwrap();
+  let m = p(h[hm]);
+  // the nearest intersection of the ray +x from m with the ring edges
+  let n = ring.len();
+  let mut best: Option<(F, usize)> = None;
+  for k in 0..n {
+    let (a, b) = (p(ring[k]), p(ring[(k + 1) % n]));
+    if (a[1] > m[1]) == (b[1] > m[1]) { continue; }
+    let x = a[0] + (m[1] - a[1]) * (b[0] - a[0]) / (b[1] - a[1]);
+    if x < m[0] { continue; }
+    if best.is_none_or(|(bx, _)| x < bx) { best = Some((x, k)); }
+  }
+  let Some((x, k)) = best else { return ring; }; // hole is outside
+  let (a, b) = (p(ring[k]), p(ring[(k + 1) % n]));
+  let mut v = if a[0] > b[0] { k } else { (k + 1) % n };
+  // reflex vertices in the triangle (m, i, v) hide v: the smallest angle
+  let i = [x, m[1]];
+  let pv = p(ring[v]);
+  let (t0, t1) = if pv[1] < m[1] { (pv, i) } else { (i, pv) };
+  let mut ang = None::<F>;
+  for j in 0..n {
+    let q = p(ring[j]);
+    if q == pv || !in_tri(&q, &m, &t0, &t1) { continue; }
+    let (pr, nx) = (p(ring[(j + n - 1) % n]), p(ring[(j + 1) % n]));
+    if cross3(&pr, &q, &nx) > <F>::from(0).unwrap() { continue; } // convex
+    let d = [q[0] - m[0], q[1] - m[1]];
+    let a = (d[1].abs()).atan2(d[0]);
+    if ang.is_none_or(|g| a < g) { ang = Some(a); v = j; }
+  }
+  let mut r = ring[..=v].to_vec();
+  r.extend((0..=h.len()).map(|j| h[(hm + j) % h.len()]));
+  r.extend_from_slice(&ring[v..]);
+  r
+}
+
+/// ear clipping on the ring (counter clockwise)
+fn ear_clip<F: Float>(pts: &[[F; 2]], mut ring: Vec<u32>) -> Vec<[u32; 3]> {
+  let o = <F>::from(0).unwrap();
+  let p = |i: u32| pts[i as usize];
+  let mut tri = vec![];
+  while ring.len() > 3 {
+    let n = ring.len();
+    let ear = (0..n).find(|&k| {
+      let (a, b, c) = (ring[(k + n - 1) % n], ring[k], ring[(k + 1) % n]);
+      let (pa, pb, pc) = (p(a), p(b), p(c));
+      if cross3(&pa, &pb, &pc) <= o { return false; } // reflex or flat
+      !ring.iter().any(|&j| {
+        let q = p(j);
+        q != pa && q != pb && q != pc && in_tri(&q, &pa, &pb, &pc)
+      })
+    }).unwrap_or_else(|| (0..n).max_by(|&i, &j| { // no ear (degenerated)
+      let c = |k: usize| cross3(&p(ring[(k + n - 1) % n]), &p(ring[k]),
+        &p(ring[(k + 1) % n]));
+      c(i).partial_cmp(&c(j)).unwrap_or(std::cmp::Ordering::Equal)
+    }).unwrap());
+    tri.push([ring[(ear + n - 1) % n], ring[ear], ring[(ear + 1) % n]]);
+    ring.remove(ear);
+  }
+  if ring.len() == 3 { tri.push([ring[0], ring[1], ring[2]]); }
+  tri
+}
@@ -0,0 +1,224 @@
+//! Validate manifold watertight orientation on polyhedron faces for Rust
+//!
+//! vertices are welded with the precision (split render vertices are joined)
+//!
+
+use std::collections::{HashMap, VecDeque};
+
+use num::Float;
+
+use crate::{merge_vertices, sub_f3, cross_f3, dot_f3};
+use crate::error::PHError;
+
+/// Validation (vertex ids are the first coincident render vertex)
+#[derive(Debug, Clone)]
+pub struct Validation<F: Float> {
+  /// open boundary edges (directed as the only one triangle)
+  pub open: Vec<(u32, u32)>,
+  /// non manifold edges (shared by more than 2 triangles)
+  pub non_manifold: Vec<(u32, u32)>,
+  /// edges of the adjacent triangles wound in the same direction
+  pub inconsistent: Vec<(u32, u32)>,
+  /// degenerated triangles (face index, triangle index)
+  pub degenerate: Vec<(usize, usize)>,
+  /// triangles with out of range index (face index, triangle index)
+  pub out_of_range: Vec<(usize, usize)>,
+  /// signed volume
+  pub vol: F,
+  /// number of vertices (welded and used)
+  pub v: usize,
+  /// number of edges
+  pub e: usize,
+  /// number of triangles (without degenerated and out of range)
+  pub f: usize,
+  /// connected components
+  pub components: usize
+}
+
+/// Validation
+impl<F: Float> Validation<F> {
+  /// Euler characteristic V - E + F
+  pub fn euler(&self) -> i64 {
+    self.v as i64 - self.e as i64 + self.f as i64
+  }
+  /// genus of the closed orientable surface (None when not)
+  pub fn genus(&self) -> Option<i64> {
+    if !self.is_watertight() || !self.is_oriented() { return None; }
+    Some(self.components as i64 - self.euler() / 2)
+  }
+  /// no open edges and no non manifold edges
+  pub fn is_watertight(&self) -> bool {
+    self.open.is_empty() && self.non_manifold.is_empty()
+  }
+  /// adjacent triangles are wound consistently
+  pub fn is_oriented(&self) -> bool {
+    self.inconsistent.is_empty()
+  }
+  /// watertight, oriented, no bad triangles and positive volume
+  pub fn is_valid(&self) -> bool {
+    self.is_watertight() && self.is_oriented() && self.degenerate.is_empty()
+    && self.out_of_range.is_empty() && self.vol > <F>::from(0).unwrap()
+  }
+  /// check (Degenerate with the first reason when not valid)
+  pub fn check(&self) -> Result<(), PHError> {
+    let n = [
+      (self.out_of_range.len(), "triangles with out of range index"),
+      (self.degenerate.len(), "degenerated triangles"),
+      (self.open.len(), "open edges"),
+      (self.non_manifold.len(), "non manifold edges"),
+      (self.inconsistent.len(), "inconsistent winding edges")];
+    if let Some((k, s)) = n.iter().find(|(k, _)| *k > 0) {
+      return Err(PHError::Degenerate(format!("{} {}", k, s)));
+    }
+    if self.vol.is_nan() || self.vol <= <F>::from(0).unwrap() {
+      return Err(PHError::Degenerate("negative signed volume".to_string()));
+    }
+    Ok(())
+  }
+}
+
+/// welded triangles ((face index, triangle index), welded ids)
+type Welded = Vec<((usize, usize), [u32; 3])>;
+
+/// triangles (face index, triangle index)
+type Tris = Vec<(usize, usize)>;
+
+/// undirected edge (sorted) to the triangles (index in welded, forward)
+type EdgeTris = HashMap<(u32, u32), Vec<(usize, bool)>>;
+
+/// weld and classify the triangles (welded, out of range, degenerated)
+fn welded<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]], p: F) ->
+  (Welded, Tris, Tris) {
+  let m = merge_vertices(vtx, p);
+  let (mut ts, mut bad, mut deg) = (vec![], vec![], vec![]);
+  for (fi, f) in idx.iter().enumerate() {
+    for (ti, t) in f.iter().enumerate() {
+      if t.iter().any(|&i| i as usize >= vtx.len()) {
+        bad.push((fi, ti));
+        continue;
+      }
+      let w = t.map(|i| m[i as usize]);
+      let [a, b, c] = t.map(|i| vtx[i as usize]);
+      let n = cross_f3(&sub_f3(&b, &a), &sub_f3(&c, &a));
+      let s = dot_f3(&n, &n).sqrt() / <F>::from(2).unwrap(); // area
+      if w[0] == w[1] || w[1] == w[2] || w[2] == w[0]
+      || s.is_nan() || s <= p * p {
+        deg.push((fi, ti));
+        continue;
+      }
+      ts.push(((fi, ti), w));
+    }
+  }
+  (ts, bad, deg)
+}
+
+/// edge map with the edges in the found order
+fn edge_map(ts: &Welded) -> (EdgeTris, Vec<(u32, u32)>) {
+  let mut em = EdgeTris::new();
+  let mut es = vec![];
+  for (k, (_, t)) in ts.iter().enumerate() {
+    for j in 0..3 {
+      let (a, b) = (t[j], t[(j + 1) % 3]);
+      let e = (a.min(b), a.max(b));
+      em.entry(e).or_insert_with(|| { es.push(e); vec![] }).push((k, a < b));
+    }
+  }
+  (em, es)
+}
+
+/// signed volume of the triangles
+fn volume<F: Float>(vtx: &[[F; 3]], ts: &[[u32; 3]]) -> F {
+  ts.iter().fold(<F>::from(0).unwrap(), |s, t| {
+    let [a, b, c] = t.map(|i| vtx[i as usize]);
+    s + dot_f3(&a, &cross_f3(&b, &c))
+  }) / <F>::from(6).unwrap()
+}
+
+/// validate
+/// - idx: index of triangles on each faces
+/// - vtx: vertices
+/// - p: precision for equality (welds vertices, minimum area is p * p)
+pub fn validate<F: Float>(idx: &[Vec<[u32; 3]>], vtx: &[[F; 3]],
+  p: F) -> Validation<F> {
+  let (ts, out_of_range, degenerate) = welded(idx, vtx, p);
+  let (em, es) = edge_map(&ts);
+  let (mut open, mut non_manifold, mut inconsistent) = (vec![], vec![], vec![]);
+  let mut uf = (0..vtx.len()).collect::<Vec<_>>(); // union find on vertices
+  fn root(uf: &mut [usize], i: usize) -> usize {
+    let mut r = i;
+    while uf[r] != r { r = uf[r]; }
+    let mut i = i;
+    while uf[i] != r { let n = uf[i]; uf[i] = r; i = n; }
+    r
+  }
+  for e in es.iter() {
+    let (ra, rb) = (root(&mut uf, e.0 as usize), root(&mut uf, e.1 as usize));
+    uf[ra] = rb;
+    match em[e][..] {
+    [(_, d)] => open.push(if d { *e } else { (e.1, e.0) }),
+    [(_, d0), (_, d1)] => if d0 == d1 { inconsistent.push(*e); },
+    _ => non_manifold.push(*e)
+    }
+  }
+  let mut used = ts.iter().flat_map(|(_, t)| t.iter().map(|&i| i as usize))
+    .collect::<Vec<_>>();
+  used.sort();
+  used.dedup();
+  let mut roots = used.iter().map(|&i| root(&mut uf, i)).collect::<Vec<_>>();
+  roots.sort();
+  roots.dedup();
+  let tw = ts.iter().map(|(_, t)| *t).collect::<Vec<_>>();
+  Validation{open, non_manifold, inconsistent, degenerate, out_of_range,
+    vol: volume(vtx, &tw), v: used.len(), e: es.len(), f: ts.len(),
+    components: roots.len()}
+}
+
+/// fix winding (consistent with the neighbours and outward of each part)
+/// returns the number of flipped triangles (uv are flipped together)
+/// - idx: index of triangles on each faces
+/// - uv: uv of triangles on each faces (may be empty)
+/// - vtx: vertices
+/// - p: precision for equality (welds vertices, minimum area is p * p)
+pub fn fix_winding<F: Float>(idx: &mut [Vec<[u32; 3]>],
+  uv: &mut [Vec<[[F; 2]; 3]>], vtx: &[[F; 3]], p: F) -> usize {
+  let (ts, _, _) = welded(idx, vtx, p);
+  let (em, _) = edge_map(&ts);
+  let mut flip = vec![None::<bool>; ts.len()];
+  for s in 0..ts.len() {
+    if flip[s].is_some() { continue; }
+    flip[s] = Some(false);
+    let mut part = vec![s];
+    let mut q = VecDeque::from([s]);
+    while let Some(k) = q.pop_front() {
+      let t = ts[k].1;
+      for j in 0..3 {
+        let (a, b) = (t[j], t[(j + 1) % 3]);
+        let e = (a.min(b), a.max(b));
+        let fk = flip[k].unwrap();
+        let &[(k0, d0), (k1, d1)] = &em[&e][..] else { continue; };
+        let (n, dn, dk) = if k0 == k { (k1, d1, d0) } else { (k0, d0, d1) };
+        let f = (dn == dk) != fk; // same direction after flip k: flip n
+        if flip[n].is_none() {
+          flip[n] = Some(f);
+          part.push(n);
+          q.push_back(n);
+        }
+      }
+    }
+    let tw = part.iter().map(|&k| match flip[k].unwrap() {
+      true => [ts[k].1[0], ts[k].1[2], ts[k].1[1]],
+      false => ts[k].1
+    }).collect::<Vec<_>>();
+    if volume(vtx, &tw) < <F>::from(0).unwrap() {
+      for &k in part.iter() { flip[k] = flip[k].map(|f| !f); }
+    }
+  }
+  let mut n = 0;
+  for (k, ((fi, ti), _)) in ts.iter().enumerate() {
+    if flip[k] != Some(true) { continue; }
+    idx[*fi][*ti].swap(1, 2);
+    if !uv.is_empty() { uv[*fi][*ti].swap(1, 2); }
+    n += 1;
+  }
+  n
+}
@@ -0,0 +1,79 @@
+//! Weld (position only topology of the split render vertices) for Rust
+//!
+//! render vertices are split by uv or face (e.g. Cube has 24 for 8 corners)
+//! topology vertices are the welded positions (the first render vertex)
+//!
+
+use num::Float;
+
+use crate::polyhedron::{TUV, Polyhedron, Edges};
+use crate::{merge_vertices, calc_cg_with_volume, calc_edges};
+
+/// Weld
+#[derive(Debug, Clone)]
+pub struct Weld<F: Float> {
+  /// vtx: topology vertices (canonical position)
+  pub vtx: Vec<[F; 3]>,
+  /// tri: [n][m] the same faces as the source with topology vertex ids
+  pub tri: Vec<Vec<[u32; 3]>>,
+  /// map: topology vertex id of each render vertex
+  pub map: Vec<u32>,
+  /// split: render vertex ids of each topology vertex
+  pub split: Vec<Vec<u32>>,
+  /// p: precision for equality (of the weld)
+  pub p: F
+}
+
+/// Weld
+impl<F: Float + std::fmt::Debug> Weld<F> where F: std::iter::Sum {
+  /// construct
+  /// - vtx: render vertices
+  /// - tri: indexed triangles of the render vertices
+  /// - p: precision for equality (same as calc_cg_f3)
+  pub fn new(vtx: &[[F; 3]], tri: &[Vec<[u32; 3]>], p: F) -> Self {
+    let m = merge_vertices(vtx, p);
+    let mut ids = vec![u32::MAX; vtx.len()];
+    let mut vs = vec![];
+    let mut split = vec![];
+    let map = m.iter().enumerate().map(|(i, &k)| {
+      if ids[k as usize] == u32::MAX {
+        ids[k as usize] = vs.len() as u32;
+        vs.push(vtx[k as usize]);
+        split.push(vec![]);
+      }
+      let t = ids[k as usize];
+      split[t as usize].push(i as u32);
+      t
+    }).collect::<Vec<_>>();
+    let tri = tri.iter().map(|f|
+      f.iter().map(|t| t.map(|i| map[i as usize])).collect()
+    ).collect();
+    Weld{vtx: vs, tri, map, split, p}
+  }
+
+  /// construct from the polyhedron
+  /// - p: precision for equality (same as calc_cg_f3)
+  pub fn from_polyhedron<T: TUV<F> + ?Sized>(ph: &T, p: F) -> Self {
+    Self::new(ph.ref_vtx(), ph.ref_tri(), p)
+  }
+
+  /// number of degenerated triangles (collapsed by the weld)
+  pub fn degenerated(&self) -> usize {
+    self.tri.iter().flatten()
+      .filter(|t| t[0] == t[1] || t[1] == t[2] || t[2] == t[0]).count()
+  }
+
+  /// calc edges (duplex) on the topology vertices
+  /// - inner: true includes the edges inside a face
+  pub fn calc_edges(&self, inner: bool) -> Edges {
+    calc_edges(&self.tri, &self.vtx, None, inner)
+  }
+
+  /// to polyhedron (position only, without uv)
+  pub fn to_polyhedron(&self) -> Polyhedron<F> {
+    let vtx = self.vtx.clone();
+    let tri = self.tri.clone();
+    let (_cg, vol) = calc_cg_with_volume(&tri, &vtx, self.p);
+    Polyhedron{vtx, tri, uv: vec![], vol, center: false}
+  }
+}